@@ -1,6 +1,6 @@
 // File: benches/ascii_conversion.rs
 
-use ascii_webcam::ascii::process_frame;
+use ascii_webcam::ascii::{process_frame, RenderMode, RenderSettings};
 use criterion::{black_box, criterion_group, criterion_main, Criterion};
 use opencv::imgcodecs;
 
@@ -8,13 +8,22 @@ fn benchmark_process_frame(c: &mut Criterion) {
     // Load a sample image
     let img = imgcodecs::imread("assets/pexels-cat.jpg", imgcodecs::IMREAD_COLOR)
         .expect("Failed to load sample image");
+    let settings = RenderSettings::default();
 
     c.bench_function("process_frame 640x480", |b| {
-        b.iter(|| process_frame(black_box(&img), black_box(640), black_box(480)))
+        b.iter(|| process_frame(black_box(&img), black_box(640), black_box(480), &settings))
     });
 
     c.bench_function("process_frame 1280x720", |b| {
-        b.iter(|| process_frame(black_box(&img), black_box(1280), black_box(720)))
+        b.iter(|| process_frame(black_box(&img), black_box(1280), black_box(720), &settings))
+    });
+
+    let braille = RenderSettings {
+        mode: RenderMode::Braille,
+        ..RenderSettings::default()
+    };
+    c.bench_function("process_frame braille 640x480", |b| {
+        b.iter(|| process_frame(black_box(&img), black_box(640), black_box(480), &braille))
     });
 }
 
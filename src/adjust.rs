@@ -0,0 +1,245 @@
+//! # Image Adjustments
+//!
+//! This module adjusts the grayscale frame before it is converted to glyphs,
+//! so that dim or washed-out webcam images still use the whole character ramp.
+//! Histogram equalization or CLAHE runs first, followed by the levels
+//! stretch, brightness, contrast and gamma, which are combined into a single
+//! lookup table. The block renderers, which show the colors of the frame
+//! rather than glyphs, carry the change of luminance over to the colors.
+
+use crate::error::Result;
+use crate::exposure::Levels;
+use color_eyre::eyre::WrapErr;
+use opencv::{
+    core::{self, Mat, Scalar, Size},
+    imgproc,
+    prelude::*,
+};
+use std::fmt;
+
+/// The available histogram equalization methods.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Equalization {
+    /// No equalization.
+    #[default]
+    Off,
+    /// Global histogram equalization.
+    Histogram,
+    /// Contrast Limited Adaptive Histogram Equalization, which equalizes each
+    /// region of the image separately without amplifying noise.
+    Clahe,
+}
+
+impl Equalization {
+    /// All equalization methods, in the order they are cycled through.
+    pub const ALL: [Equalization; 3] = [
+        Equalization::Off,
+        Equalization::Histogram,
+        Equalization::Clahe,
+    ];
+
+    /// Returns the equalization method following this one, wrapping around at the end.
+    #[must_use]
+    pub fn next(self) -> Equalization {
+        let index = Equalization::ALL
+            .iter()
+            .position(|&method| method == self)
+            .unwrap_or(0);
+        Equalization::ALL[(index + 1) % Equalization::ALL.len()]
+    }
+}
+
+impl fmt::Display for Equalization {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Equalization::Off => "off",
+            Equalization::Histogram => "histogram",
+            Equalization::Clahe => "clahe",
+        };
+        f.write_str(name)
+    }
+}
+
+/// The adjustments applied to the grayscale frame.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Adjustments {
+    /// The offset added to every luminance value, from `-255` to `255`.
+    pub brightness: i32,
+    /// The gain applied around mid-gray, `1.0` leaving the image unchanged.
+    pub contrast: f64,
+    /// The gamma correction, values above `1.0` brightening the midtones.
+    pub gamma: f64,
+    /// The histogram equalization applied before the other adjustments.
+    pub equalization: Equalization,
+    /// The black and white points stretched to the full range before brightness,
+    /// contrast and gamma, usually set by auto-exposure.
+    pub levels: Levels,
+}
+
+impl Adjustments {
+    /// The change of brightness for each key press.
+    pub const BRIGHTNESS_STEP: i32 = 8;
+
+    /// The change of contrast and gamma for each key press.
+    pub const FACTOR_STEP: f64 = 0.1;
+
+    /// The lowest and highest contrast and gamma.
+    const FACTOR_RANGE: (f64, f64) = (0.1, 4.0);
+
+    /// Changes the brightness by `delta`, within `-255` to `255`.
+    pub fn adjust_brightness(&mut self, delta: i32) {
+        self.brightness = (self.brightness + delta).clamp(-255, 255);
+    }
+
+    /// Changes the contrast by `delta`, within `0.1` to `4.0`.
+    pub fn adjust_contrast(&mut self, delta: f64) {
+        self.contrast = clamp_factor(self.contrast + delta);
+    }
+
+    /// Changes the gamma by `delta`, within `0.1` to `4.0`.
+    pub fn adjust_gamma(&mut self, delta: f64) {
+        self.gamma = clamp_factor(self.gamma + delta);
+    }
+
+    /// Returns `true` if the levels, brightness, contrast and gamma leave the image unchanged.
+    #[must_use]
+    pub fn is_neutral(&self) -> bool {
+        self.levels.is_full_range()
+            && self.brightness == 0
+            && (self.contrast - 1.0).abs() < f64::EPSILON
+            && (self.gamma - 1.0).abs() < f64::EPSILON
+    }
+
+    /// Returns the table mapping each luminance value to its adjusted value.
+    ///
+    /// The levels are stretched to the full range first. Contrast is then applied
+    /// around mid-gray, brightness is added and the result is gamma corrected.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use ascii_webcam::adjust::Adjustments;
+    /// let adjustments = Adjustments { brightness: 10, ..Adjustments::default() };
+    /// let table = adjustments.lookup_table();
+    /// assert_eq!(table[0], 10);
+    /// assert_eq!(table[255], 255);
+    /// ```
+    #[must_use]
+    pub fn lookup_table(&self) -> [u8; 256] {
+        let mut table = [0; 256];
+        for (value, entry) in (0u8..=255).zip(table.iter_mut()) {
+            let level = self.levels.stretch(f64::from(value));
+            let level = (level - 128.0) * self.contrast + 128.0 + f64::from(self.brightness);
+            let level = (level / 255.0).clamp(0.0, 1.0).powf(1.0 / self.gamma) * 255.0;
+            // The level is clamped to the range of `u8` above.
+            #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+            {
+                *entry = level.round() as u8;
+            }
+        }
+        table
+    }
+}
+
+impl Default for Adjustments {
+    fn default() -> Self {
+        Adjustments {
+            brightness: 0,
+            contrast: 1.0,
+            gamma: 1.0,
+            equalization: Equalization::default(),
+            levels: Levels::default(),
+        }
+    }
+}
+
+impl fmt::Display for Adjustments {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Brightness: {:+} | Contrast: {:.1} | Gamma: {:.1} | Equalize: {}",
+            self.brightness, self.contrast, self.gamma, self.equalization
+        )
+    }
+}
+
+/// Clamps a contrast or gamma factor to its allowed range.
+fn clamp_factor(factor: f64) -> f64 {
+    let (low, high) = Adjustments::FACTOR_RANGE;
+    // Rounding keeps repeated steps from accumulating floating-point drift.
+    ((factor * 10.0).round() / 10.0).clamp(low, high)
+}
+
+/// Applies the adjustments to an 8-bit grayscale image.
+///
+/// # Arguments
+///
+/// * `gray` - The grayscale image to adjust
+/// * `adjustments` - The adjustments to apply
+///
+/// # Returns
+///
+/// Returns the adjusted image, or `None` if there is nothing to adjust.
+///
+/// # Errors
+///
+/// This function may return an error if:
+/// - Histogram equalization or CLAHE fails
+/// - Building or applying the lookup table fails
+pub fn apply(gray: &Mat, adjustments: &Adjustments) -> Result<Option<Mat>> {
+    let equalized = equalize(gray, adjustments.equalization)?;
+    let corrected = correct(equalized.as_ref().unwrap_or(gray), adjustments)?;
+    Ok(corrected.or(equalized))
+}
+
+/// Equalizes the histogram of an 8-bit grayscale image.
+///
+/// # Returns
+///
+/// Returns the equalized image, or `None` if equalization is off.
+///
+/// # Errors
+///
+/// This function returns an error if histogram equalization or CLAHE fails.
+pub fn equalize(gray: &Mat, equalization: Equalization) -> Result<Option<Mat>> {
+    let mut equalized = Mat::default();
+    match equalization {
+        Equalization::Off => return Ok(None),
+        Equalization::Histogram => {
+            imgproc::equalize_hist(gray, &mut equalized)
+                .wrap_err("failed to equalize histogram")?;
+        }
+        Equalization::Clahe => {
+            let mut clahe =
+                imgproc::create_clahe(2.0, Size::new(8, 8)).wrap_err("failed to create CLAHE")?;
+            clahe
+                .apply(gray, &mut equalized)
+                .wrap_err("failed to apply CLAHE")?;
+        }
+    }
+    Ok(Some(equalized))
+}
+
+/// Applies the levels, brightness, contrast and gamma to an 8-bit image, with one
+/// lookup table shared by all channels.
+///
+/// # Returns
+///
+/// Returns the corrected image, or `None` if the adjustments are neutral.
+///
+/// # Errors
+///
+/// This function returns an error if building or applying the lookup table fails.
+pub fn correct(image: &Mat, adjustments: &Adjustments) -> Result<Option<Mat>> {
+    if adjustments.is_neutral() {
+        return Ok(None);
+    }
+    let mut table = Mat::new_rows_cols_with_default(1, 256, core::CV_8UC1, Scalar::all(0.0))?;
+    table
+        .data_bytes_mut()
+        .wrap_err("failed to access lookup table")?
+        .copy_from_slice(&adjustments.lookup_table());
+    let mut corrected = Mat::default();
+    core::lut(image, &table, &mut corrected).wrap_err("failed to apply lookup table")?;
+    Ok(Some(corrected))
+}
@@ -3,24 +3,68 @@
 //! This module contains the `App` struct which represents the application state
 //! and provides methods for updating and rendering the application.
 
-use crate::ascii::process_frame;
+use crate::adjust::Adjustments;
+use crate::ascii::{process_frame_with_exposure, RenderSettings};
+use crate::aspect::{self, FitMode, DEFAULT_CELL_ASPECT};
+use crate::color::ColorMode;
+use crate::devices::{CameraInfo, DevicePicker};
 use crate::error::Result;
+use crate::exposure::AutoExposure;
+use crate::frame::AsciiFrame;
+use crate::keymap::{self, Action, Keymap};
+use crate::playback::Playback;
+use crate::reconnect::Connection;
+use crate::source::SourceProperties;
 use color_eyre::eyre::WrapErr;
-use opencv::core::Mat;
+use opencv::{
+    core::{self, Mat},
+    prelude::*,
+};
 use ratatui::{
     layout::{Constraint, Direction, Layout, Rect},
     style::{Color, Style, Stylize},
     text::{Line, Span},
-    widgets::{Block, Borders, Clear, Paragraph},
+    widgets::{Block, Borders, Clear, LineGauge, List, ListItem, ListState, Paragraph},
     Frame,
 };
+use std::rc::Rc;
 
 /// Represents the state of the application.
-#[derive(Default)]
 pub struct App {
-    pub ascii_frame: String,
+    pub ascii_frame: AsciiFrame,
+    pub color_mode: ColorMode,
+    pub settings: RenderSettings,
+    pub exposure: AutoExposure,
+    pub fit: FitMode,
+    /// The width of a terminal cell divided by its height.
+    pub cell_aspect: f64,
+    /// Whether frames are flipped horizontally, like a mirror.
+    pub mirror: bool,
     pub fps: f64,
     pub show_help: bool,
+    /// Whether the Stats block is shown above the frame.
+    pub show_stats: bool,
+    /// Whether the instructions, or the playback progress, are shown below the frame.
+    pub show_status: bool,
+    /// Why the configuration file could not be reloaded, if it could not.
+    pub config_error: Option<String>,
+    /// The keys bound to each action, shown in the help menu and the instructions.
+    pub keymap: Keymap,
+    /// The properties of the current source, as negotiated with the device.
+    pub source: SourceProperties,
+    /// The playback state of sources with a known length, shown as a progress bar.
+    pub playback: Option<Playback>,
+    /// The camera picker popup, while open.
+    pub device_picker: Option<DevicePicker>,
+    /// Whether the source delivers frames. While it is being reconnected, the
+    /// last frame stays on screen under an overlay.
+    pub connection: Connection,
+}
+
+impl Default for App {
+    fn default() -> Self {
+        App::new()
+    }
 }
 
 impl App {
@@ -31,26 +75,43 @@ impl App {
     /// ```
     /// use ascii_webcam::app::App;
     /// let app = App::new();
-    /// assert_eq!(app.ascii_frame, "");
+    /// assert!(app.ascii_frame.is_empty());
     /// assert_eq!(app.fps, 0.0);
     /// assert_eq!(app.show_help, false);
     /// ```
     #[must_use]
     pub fn new() -> App {
         App {
-            ascii_frame: String::new(),
+            ascii_frame: AsciiFrame::default(),
+            color_mode: ColorMode::Mono,
+            settings: RenderSettings::default(),
+            exposure: AutoExposure::default(),
+            fit: FitMode::default(),
+            cell_aspect: DEFAULT_CELL_ASPECT,
+            mirror: false,
             fps: 0.0,
             show_help: false,
+            show_stats: true,
+            show_status: true,
+            config_error: None,
+            keymap: Keymap::default(),
+            source: SourceProperties::default(),
+            playback: None,
+            device_picker: None,
+            connection: Connection::Connected,
         }
     }
 
     /// Updates the application state with a new video frame.
     ///
+    /// The frame keeps its proportions on screen according to the fit mode and the
+    /// cell aspect ratio. When there is no room for it, the ASCII frame is empty.
+    ///
     /// # Arguments
     ///
     /// * `frame` - The video frame to process
-    /// * `width` - The width to resize the frame to
-    /// * `height` - The height to resize the frame to
+    /// * `width` - The number of available cells per row
+    /// * `height` - The number of available rows of cells
     ///
     /// # Returns
     ///
@@ -59,11 +120,65 @@ impl App {
     /// # Errors
     ///
     /// This function may return an error if:
-    /// - The frame processing fails
+    /// - The frame processing, including measuring its exposure, fails
     /// - There are issues with resizing or converting the frame
     pub fn update(&mut self, frame: &Mat, width: i32, height: i32) -> Result<()> {
-        self.ascii_frame =
-            process_frame(frame, width, height).wrap_err("failed to process frame")?;
+        // Small terminals leave no room for the frame.
+        if width <= 0 || height <= 0 {
+            self.ascii_frame = AsciiFrame::default();
+            return Ok(());
+        }
+        let flipped;
+        let frame = if self.mirror {
+            let mut mirrored = Mat::default();
+            core::flip(frame, &mut mirrored, 1).wrap_err("failed to mirror frame")?;
+            flipped = mirrored;
+            &flipped
+        } else {
+            frame
+        };
+        let placement = aspect::place(
+            frame.cols(),
+            frame.rows(),
+            width,
+            height,
+            self.cell_aspect,
+            self.fit,
+        );
+        if placement.width <= 0 || placement.height <= 0 {
+            self.ascii_frame = AsciiFrame::default();
+            return Ok(());
+        }
+        let cropped;
+        let source =
+            if placement.crop.width == frame.cols() && placement.crop.height == frame.rows() {
+                frame
+            } else {
+                cropped = Mat::roi(frame, placement.crop)
+                    .and_then(|roi| roi.try_clone())
+                    .wrap_err("failed to crop frame")?;
+                &cropped
+            };
+
+        let converted = process_frame_with_exposure(
+            source,
+            placement.width,
+            placement.height,
+            &self.settings,
+            &mut self.exposure,
+        )
+        .wrap_err("failed to process frame")?;
+        if self.exposure.enabled {
+            self.settings.adjustments.levels = self.exposure.levels();
+        }
+        self.ascii_frame = if self.fit == FitMode::Letterbox {
+            converted.letterbox(
+                usize::try_from(width).unwrap_or(0),
+                usize::try_from(height).unwrap_or(0),
+            )
+        } else {
+            converted
+        };
         Ok(())
     }
 
@@ -72,54 +187,342 @@ impl App {
         self.show_help = !self.show_help;
     }
 
+    /// Opens the camera picker, highlighting the camera currently in use.
+    pub fn open_device_picker(&mut self, cameras: Vec<CameraInfo>, current: Option<i32>) {
+        self.device_picker = Some(DevicePicker::new(cameras, current));
+    }
+
+    /// Closes the camera picker.
+    pub fn close_device_picker(&mut self) {
+        self.device_picker = None;
+    }
+
+    /// Toggles between monochrome output and the best color mode the terminal supports.
+    pub fn toggle_color(&mut self) {
+        self.color_mode = if self.color_mode == ColorMode::Mono {
+            ColorMode::detect()
+        } else {
+            ColorMode::Mono
+        };
+    }
+
+    /// Switches to the next built-in character ramp.
+    pub fn next_ramp(&mut self) {
+        self.settings.ramp = self.settings.ramp.next_preset();
+    }
+
+    /// Toggles the inversion of the character ramp for light-background terminals.
+    pub fn invert_ramp(&mut self) {
+        self.settings.ramp.invert();
+    }
+
+    /// Switches to the next render mode.
+    ///
+    /// Colors are enabled automatically when switching to a block mode, since those
+    /// cannot show the image without them.
+    pub fn next_render_mode(&mut self) {
+        self.settings.mode = self.settings.mode.next();
+        if self.settings.mode.needs_color() && self.color_mode == ColorMode::Mono {
+            self.color_mode = ColorMode::detect();
+        }
+    }
+
+    /// Switches to the next edge detector.
+    pub fn next_edge_mode(&mut self) {
+        self.settings.edges = self.settings.edges.next();
+    }
+
+    /// Brightens or darkens the image by one step.
+    pub fn adjust_brightness(&mut self, brighter: bool) {
+        let step = Adjustments::BRIGHTNESS_STEP;
+        self.settings
+            .adjustments
+            .adjust_brightness(if brighter { step } else { -step });
+    }
+
+    /// Raises or lowers the contrast by one step.
+    pub fn adjust_contrast(&mut self, higher: bool) {
+        let step = Adjustments::FACTOR_STEP;
+        self.settings
+            .adjustments
+            .adjust_contrast(if higher { step } else { -step });
+    }
+
+    /// Raises or lowers the gamma by one step.
+    pub fn adjust_gamma(&mut self, higher: bool) {
+        let step = Adjustments::FACTOR_STEP;
+        self.settings
+            .adjustments
+            .adjust_gamma(if higher { step } else { -step });
+    }
+
+    /// Switches to the next histogram equalization method.
+    pub fn next_equalization(&mut self) {
+        let adjustments = &mut self.settings.adjustments;
+        adjustments.equalization = adjustments.equalization.next();
+    }
+
+    /// Toggles auto-exposure, which stretches the levels of each frame to the full range.
+    pub fn toggle_auto_exposure(&mut self) {
+        self.exposure.toggle();
+        self.settings.adjustments.levels = self.exposure.levels();
+    }
+
+    /// Resets the brightness, contrast, gamma and equalization.
+    pub fn reset_adjustments(&mut self) {
+        self.settings.adjustments = Adjustments::default();
+    }
+
+    /// Switches to the next fit mode.
+    pub fn next_fit_mode(&mut self) {
+        self.fit = self.fit.next();
+    }
+
+    /// Makes the cells narrower or wider by one step, to correct the aspect ratio
+    /// when the terminal does not report its cell size.
+    pub fn adjust_cell_aspect(&mut self, wider: bool) {
+        let (low, high) = aspect::CELL_ASPECT_RANGE;
+        let step = if wider { 0.05 } else { -0.05 };
+        self.cell_aspect = ((self.cell_aspect + step) * 100.0).round() / 100.0;
+        self.cell_aspect = self.cell_aspect.clamp(low, high);
+    }
+
+    /// Switches to the next dithering method.
+    pub fn next_dither(&mut self) {
+        self.settings.dither = self.settings.dither.next();
+    }
+
+    /// Returns the area inside the border of the ASCII block, where the frame is drawn.
+    ///
+    /// Frames should be converted to exactly the size of this area, so that they are
+    /// neither clipped nor smaller than the block.
+    ///
+    /// # Arguments
+    ///
+    /// * `area` - The area of the whole terminal
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use ascii_webcam::app::App;
+    /// use ratatui::layout::Rect;
+    /// let mut app = App::new();
+    /// let area = app.frame_area(Rect::new(0, 0, 80, 24));
+    /// assert_eq!((area.width, area.height), (78, 16));
+    /// app.show_stats = false;
+    /// assert_eq!(app.frame_area(Rect::new(0, 0, 80, 24)).height, 21);
+    /// ```
+    #[must_use]
+    pub fn frame_area(&self, area: Rect) -> Rect {
+        Self::ascii_block().inner(self.layout(area)[1])
+    }
+
+    /// Splits the terminal into the Stats block, the ASCII block and the instructions
+    /// line. Hidden parts take no space.
+    fn layout(&self, area: Rect) -> Rc<[Rect]> {
+        Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([
+                Constraint::Length(if self.show_stats { 5 } else { 0 }),
+                Constraint::Min(0),
+                Constraint::Length(u16::from(self.show_status)),
+            ])
+            .split(area)
+    }
+
+    /// Returns the bordered block the ASCII frame is drawn in.
+    fn ascii_block() -> Block<'static> {
+        Block::default().borders(Borders::ALL).title("ASCII Webcam")
+    }
+
     /// Renders the application UI.
     ///
     /// This method is responsible for rendering:
     /// - The FPS counter
     /// - The ASCII video frame
-    /// - The instruction text
+    /// - The instruction text, or the playback progress of video files
     /// - The help menu (if visible)
+    /// - The camera picker (if open)
     pub fn render(&self, f: &mut Frame) {
-        let chunks = Layout::default()
-            .direction(Direction::Vertical)
-            .constraints([
-                Constraint::Length(3),
-                Constraint::Min(0),
-                Constraint::Length(1),
-            ])
-            .split(f.area());
+        let chunks = self.layout(f.area());
 
-        let fps_text = format!("FPS: {:.2}", self.fps);
-        let fps_paragraph = Paragraph::new(fps_text)
+        let fps_text = format!(
+            "FPS: {:.2} | Mode: {} | Color: {} | Ramp: {} | Dither: {} | Edges: {} | Fit: {} ({:.2})",
+            self.fps,
+            self.settings.mode,
+            self.color_mode,
+            self.settings.ramp,
+            self.settings.dither,
+            self.settings.edges,
+            self.fit,
+            self.cell_aspect
+        );
+        let stats_text = vec![
+            Line::from(fps_text),
+            Line::from(format!(
+                "{} | Levels: {}{}",
+                self.settings.adjustments,
+                self.settings.adjustments.levels,
+                if self.exposure.enabled { " (auto)" } else { "" }
+            )),
+            Line::from(format!("Source: {}", self.source)),
+        ];
+        let mut stats_block = Block::default().borders(Borders::ALL).title("Stats");
+        if let Some(error) = &self.config_error {
+            stats_block = stats_block.title_bottom(Line::from(error.as_str()).red());
+        }
+        let fps_paragraph = Paragraph::new(stats_text)
             .style(Style::default().fg(Color::Cyan))
-            .block(Block::default().borders(Borders::ALL).title("Stats"));
+            .block(stats_block);
 
-        f.render_widget(fps_paragraph, chunks[0]);
+        if self.show_stats {
+            f.render_widget(fps_paragraph, chunks[0]);
+        }
 
-        let ascii_block = Block::default().borders(Borders::ALL).title("ASCII Webcam");
-        let ascii_paragraph = Paragraph::new(self.ascii_frame.as_str()).block(ascii_block);
+        let ascii_block = Self::ascii_block();
+        let ascii_paragraph =
+            Paragraph::new(self.ascii_frame.to_text(self.color_mode)).block(ascii_block);
 
         f.render_widget(ascii_paragraph, chunks[1]);
 
-        let instructions = Line::from(vec![
-            "Quit".into(),
-            " <q>".blue().bold(),
-            " | Help".into(),
-            " <?>".blue().bold(),
-        ]);
-        let instructions_paragraph = Paragraph::new(instructions)
-            .style(Style::default().fg(Color::White))
-            .alignment(ratatui::layout::Alignment::Center);
+        if self.show_status {
+            self.render_status(f, chunks[2]);
+        }
 
-        f.render_widget(instructions_paragraph, chunks[2]);
+        if self.connection != Connection::Connected {
+            self.render_connection(f);
+        }
 
         if self.show_help {
             self.render_help(f);
         }
+
+        if let Some(picker) = &self.device_picker {
+            Self::render_device_picker(f, picker);
+        }
+    }
+
+    /// Renders the instructions, or the playback progress of video files.
+    fn render_status(&self, f: &mut Frame, area: Rect) {
+        if let Some(playback) = &self.playback {
+            let progress = LineGauge::default()
+                .filled_style(Style::default().fg(Color::Cyan))
+                .unfilled_style(Style::default().fg(Color::DarkGray))
+                .label(playback.to_string())
+                .ratio(playback.progress().unwrap_or(0.0));
+
+            f.render_widget(progress, area);
+        } else {
+            let adjust = [
+                Action::Darker,
+                Action::LessContrast,
+                Action::LowerGamma,
+                Action::Equalization,
+                Action::AutoExposure,
+            ]
+            .map(|action| self.keymap.label(action))
+            .join("/");
+            let entries = [
+                ("Quit", self.keymap.label(Action::Quit)),
+                ("Color", self.keymap.label(Action::Color)),
+                ("Ramp", self.keymap.label(Action::Ramp)),
+                ("Invert", self.keymap.label(Action::InvertRamp)),
+                ("Mode", self.keymap.label(Action::RenderMode)),
+                ("Dither", self.keymap.label(Action::Dither)),
+                ("Edges", self.keymap.label(Action::Edges)),
+                ("Fit", self.keymap.label(Action::Fit)),
+                ("Adjust", adjust),
+                ("Help", self.keymap.label(Action::Help)),
+            ];
+            let mut spans = Vec::new();
+            for (i, (name, keys)) in entries.into_iter().enumerate() {
+                spans.push(if i == 0 {
+                    name.into()
+                } else {
+                    format!(" | {name}").into()
+                });
+                spans.push(format!(" <{keys}>").blue().bold());
+            }
+            let instructions = Line::from(spans);
+            let instructions_paragraph = Paragraph::new(instructions)
+                .style(Style::default().fg(Color::White))
+                .alignment(ratatui::layout::Alignment::Center);
+
+            f.render_widget(instructions_paragraph, area);
+        }
+    }
+
+    /// Renders the reconnection overlay over the frozen frame.
+    fn render_connection(&self, f: &mut Frame) {
+        let area = f.area();
+        let message = self.connection.to_string();
+        let width = u16::try_from(message.chars().count())
+            .unwrap_or(u16::MAX)
+            .saturating_add(4)
+            .min(area.width);
+        let height = 3.min(area.height);
+        let overlay_area = Rect::new(
+            (area.width - width) / 2,
+            (area.height - height) / 2,
+            width,
+            height,
+        );
+
+        f.render_widget(Clear, overlay_area);
+
+        let overlay = Paragraph::new(message)
+            .style(Style::default().fg(Color::Red).bold())
+            .block(Block::default().borders(Borders::ALL).red())
+            .alignment(ratatui::layout::Alignment::Center);
+        f.render_widget(overlay, overlay_area);
     }
 
-    /// Renders the help menu.
-    #[allow(clippy::unused_self)]
+    /// Renders the camera picker.
+    fn render_device_picker(f: &mut Frame, picker: &DevicePicker) {
+        let area = f.area();
+        let picker_area = Rect::new(
+            area.width / 4,
+            area.height / 4,
+            area.width / 2,
+            area.height / 2,
+        );
+
+        f.render_widget(Clear, picker_area);
+
+        let mut block = Block::default()
+            .title("Camera <↑/↓/Enter/Esc>")
+            .borders(Borders::ALL);
+        if let Some(error) = &picker.error {
+            block = block.title_bottom(Line::from(error.as_str()).red());
+        }
+        if picker.cameras.is_empty() {
+            let text = if picker.searching {
+                "Searching for cameras…"
+            } else {
+                "No camera found"
+            };
+            let message = Paragraph::new(text)
+                .block(block)
+                .alignment(ratatui::layout::Alignment::Center);
+            f.render_widget(message, picker_area);
+            return;
+        }
+
+        let items = picker
+            .cameras
+            .iter()
+            .map(|camera| ListItem::new(camera.to_string()))
+            .collect::<Vec<_>>();
+        let list = List::new(items)
+            .block(block)
+            .highlight_style(Style::default().fg(Color::Blue).bold())
+            .highlight_symbol("> ");
+        let mut state = ListState::default().with_selected(Some(picker.selected));
+
+        f.render_stateful_widget(list, picker_area, &mut state);
+    }
+
+    /// Renders the help menu, listing the keys of the active keymap.
     fn render_help(&self, f: &mut Frame) {
         let area = f.area();
         let help_area = Rect::new(
@@ -131,30 +534,17 @@ impl App {
 
         f.render_widget(Clear, help_area);
 
-        let help_text = vec![
-            Line::from("Help"),
-            Line::from(""),
-            Line::from(vec![
-                Span::raw("Press "),
-                Span::styled(
-                    "q",
-                    Style::default()
-                        .fg(Color::Blue)
-                        .add_modifier(ratatui::style::Modifier::BOLD),
-                ),
-                Span::raw(" to quit the application"),
-            ]),
+        let key_style = Style::default()
+            .fg(Color::Blue)
+            .add_modifier(ratatui::style::Modifier::BOLD);
+        let mut help_text = vec![Line::from("Help"), Line::from("")];
+        help_text.extend(keymap::HELP.iter().map(|(actions, description)| {
             Line::from(vec![
                 Span::raw("Press "),
-                Span::styled(
-                    "?",
-                    Style::default()
-                        .fg(Color::Blue)
-                        .add_modifier(ratatui::style::Modifier::BOLD),
-                ),
-                Span::raw(" to toggle this help menu"),
-            ]),
-        ];
+                Span::styled(self.keymap.group_label(actions), key_style),
+                Span::raw(format!(" to {description}")),
+            ])
+        }));
 
         let help_paragraph = Paragraph::new(help_text)
             .block(Block::default().title("Help").borders(Borders::ALL))
@@ -3,7 +3,17 @@
 //! This module provides functionality for converting video frames
 //! to ASCII art representations.
 
+use crate::adjust::{self, Adjustments};
+use crate::blocks::{self, BlockCell};
+use crate::braille;
+use crate::color::Rgb;
+use crate::dither::{dither, Dither};
+use crate::edges::{detect_edges, EdgeMode};
 use crate::error::Result;
+use crate::exposure::AutoExposure;
+use crate::frame::{AsciiFrame, Cell};
+use crate::ramp::CharRamp;
+use crate::shape;
 use color_eyre::eyre::WrapErr;
 use lazy_static::lazy_static;
 use opencv::{
@@ -11,9 +21,106 @@ use opencv::{
     imgproc,
     prelude::*,
 };
+use std::fmt;
 
 lazy_static! {
-    static ref ASCII_CHARS: Vec<char> = vec![' ', '.', ':', '-', '=', '+', '*', '#', '%', '@'];
+    static ref DEFAULT_RAMP: CharRamp = CharRamp::default();
+}
+
+/// The available ways of turning a frame into characters.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum RenderMode {
+    /// One pixel per cell, mapped to a glyph of the character ramp.
+    #[default]
+    Ramp,
+    /// 2x4 pixels per cell, encoded as Unicode Braille patterns.
+    Braille,
+    /// Two vertical pixels per cell, drawn as `▀` with separate colors.
+    HalfBlock,
+    /// 2x2 pixels per cell, drawn as quadrant blocks with two colors.
+    Quadrant,
+    /// 2x3 pixels per cell, drawn as Unicode 13 sextant blocks with two colors.
+    Sextant,
+    /// 2x4 pixels per cell, matched against the glyph shapes of an embedded font.
+    Shape,
+}
+
+impl RenderMode {
+    /// All render modes, in the order they are cycled through.
+    pub const ALL: [RenderMode; 6] = [
+        RenderMode::Ramp,
+        RenderMode::Braille,
+        RenderMode::HalfBlock,
+        RenderMode::Quadrant,
+        RenderMode::Sextant,
+        RenderMode::Shape,
+    ];
+
+    /// Returns the render mode following this one, wrapping around at the end.
+    #[must_use]
+    pub fn next(self) -> RenderMode {
+        let index = RenderMode::ALL
+            .iter()
+            .position(|&mode| mode == self)
+            .unwrap_or(0);
+        RenderMode::ALL[(index + 1) % RenderMode::ALL.len()]
+    }
+
+    /// Returns `true` if the render mode relies on colors to show the image.
+    #[must_use]
+    pub fn needs_color(self) -> bool {
+        matches!(
+            self,
+            RenderMode::HalfBlock | RenderMode::Quadrant | RenderMode::Sextant
+        )
+    }
+}
+
+impl fmt::Display for RenderMode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            RenderMode::Ramp => "ramp",
+            RenderMode::Braille => "braille",
+            RenderMode::HalfBlock => "half-block",
+            RenderMode::Quadrant => "quadrant",
+            RenderMode::Sextant => "sextant",
+            RenderMode::Shape => "shape",
+        };
+        f.write_str(name)
+    }
+}
+
+/// The settings controlling how a frame is converted.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RenderSettings {
+    /// The render mode.
+    pub mode: RenderMode,
+    /// The character ramp used by the ramp renderer. Its inversion also applies
+    /// to the Braille renderer.
+    pub ramp: CharRamp,
+    /// The dithering applied by the ramp and Braille renderers. Without dithering,
+    /// the Braille renderer uses a single threshold at the mean luminance of the frame.
+    pub dither: Dither,
+    /// The edge detector of the ramp renderer. Cells on an edge are drawn with a
+    /// glyph following the edge direction instead of a ramp glyph.
+    pub edges: EdgeMode,
+    /// The luminance difference across an edge needed to detect it.
+    pub edge_threshold: u8,
+    /// The adjustments applied to the grayscale frame before it is converted to glyphs.
+    pub adjustments: Adjustments,
+}
+
+impl Default for RenderSettings {
+    fn default() -> Self {
+        RenderSettings {
+            mode: RenderMode::default(),
+            ramp: CharRamp::default(),
+            dither: Dither::default(),
+            edges: EdgeMode::default(),
+            edge_threshold: 48,
+            adjustments: Adjustments::default(),
+        }
+    }
 }
 
 /// Converts a grayscale value to an ASCII character.
@@ -24,9 +131,10 @@ lazy_static! {
 ///
 /// # Returns
 ///
-/// An ASCII character from the `ASCII_CHARS` array, chosen based on the intensity of the
+/// An ASCII character from the classic ramp, chosen based on the intensity of the
 /// grayscale value. The mapping is such that `0` corresponds to the lightest character (`' '`),
-/// and `255` corresponds to the darkest character (`'@'`).
+/// and `255` corresponds to the darkest character (`'@'`). Use [`CharRamp::glyph`] to map
+/// into other ramps.
 ///
 /// # Examples
 ///
@@ -36,63 +144,261 @@ lazy_static! {
 /// ```
 #[must_use]
 pub fn get_ascii_char(value: u8) -> char {
-    let index = (value as usize * (ASCII_CHARS.len() - 1)) / 255;
-    ASCII_CHARS[index.min(ASCII_CHARS.len() - 1)]
+    DEFAULT_RAMP.glyph(value)
 }
 
 /// Processes a video frame and converts it to an ASCII art representation.
 ///
 /// # Arguments
 ///
-/// * `frame` - The video frame to process
-/// * `width` - The width to resize the frame to
-/// * `height` - The height to resize the frame to
+/// * `frame` - The BGR video frame to process
+/// * `width` - The width of the resulting frame in cells
+/// * `height` - The height of the resulting frame in cells
+/// * `settings` - The render mode and its options
 ///
 /// # Returns
 ///
-/// Returns a `Result<String>`. On success, it returns a string containing the ASCII art representation
-/// of the resized and grayscale-converted video frame. Each line of the string represents a row
-/// of ASCII characters corresponding to the pixels in the resized frame. On failure, it returns
-/// an error with a descriptive message.
+/// Returns a `Result<AsciiFrame>`. On success, it returns a frame of `width` x `height`
+/// cells. Each cell holds the character chosen by the render mode and the grayscale value
+/// of the source pixels it covers. The ramp and Braille modes color each cell with the
+/// average color of those pixels, while the block modes set both a foreground and a
+/// background color. On failure, it returns an error with a descriptive message.
 ///
 /// # Errors
 ///
 /// This function may return an error if:
+/// - The requested size is negative
 /// - Converting the frame to grayscale fails
+/// - Adjusting the grayscale frame fails
 /// - Resizing the frame fails
-/// - Accessing pixel values fails
-/// - String conversion or joining operations fail
-pub fn process_frame(frame: &Mat, width: i32, height: i32) -> Result<String> {
-    let mut gray = Mat::default();
-    imgproc::cvt_color(frame, &mut gray, imgproc::COLOR_BGR2GRAY, 0)
+/// - Accessing pixel values fails, e.g. because the frame is not a 3-channel BGR image
+pub fn process_frame(
+    frame: &Mat,
+    width: i32,
+    height: i32,
+    settings: &RenderSettings,
+) -> Result<AsciiFrame> {
+    convert(frame, width, height, settings, None)
+}
+
+/// Processes a video frame like `process_frame`, with the levels set by
+/// auto-exposure when it is enabled.
+///
+/// The levels are measured on the grayscale frame after histogram equalization,
+/// which is the image they are applied to, and replace those of `settings`.
+///
+/// # Errors
+///
+/// This function may return the same errors as `process_frame`, or an error if
+/// measuring the levels fails.
+pub fn process_frame_with_exposure(
+    frame: &Mat,
+    width: i32,
+    height: i32,
+    settings: &RenderSettings,
+    exposure: &mut AutoExposure,
+) -> Result<AsciiFrame> {
+    convert(frame, width, height, settings, Some(exposure))
+}
+
+/// Converts a video frame to ASCII art, measuring its levels if auto-exposure is given.
+fn convert(
+    frame: &Mat,
+    width: i32,
+    height: i32,
+    settings: &RenderSettings,
+    exposure: Option<&mut AutoExposure>,
+) -> Result<AsciiFrame> {
+    let cols = usize::try_from(width).wrap_err("invalid frame width")?;
+    let rows = usize::try_from(height).wrap_err("invalid frame height")?;
+
+    let mut original = Mat::default();
+    imgproc::cvt_color(frame, &mut original, imgproc::COLOR_BGR2GRAY, 0)
         .wrap_err("failed to convert frame to grayscale")?;
+    let mut adjustments = settings.adjustments;
+    let equalized = adjust::equalize(&original, adjustments.equalization)
+        .wrap_err("failed to equalize frame")?;
+    if let Some(exposure) = exposure.filter(|exposure| exposure.enabled) {
+        adjustments.levels = exposure
+            .update(equalized.as_ref().unwrap_or(&original))
+            .wrap_err("failed to measure exposure")?;
+    }
+    let corrected = adjust::correct(equalized.as_ref().unwrap_or(&original), &adjustments)
+        .wrap_err("failed to adjust frame")?;
+    let adjusted = corrected.or(equalized);
+    let adjustment = adjusted.as_ref().map(|adjusted| (&original, adjusted));
+    let gray = adjusted.as_ref().unwrap_or(&original);
+
+    let small_gray =
+        resize(gray, width, height, imgproc::INTER_LINEAR).wrap_err("failed to resize frame")?;
+    let luminance = small_gray
+        .data_bytes()
+        .wrap_err("failed to access pixels")?
+        .to_vec();
+    // Area interpolation averages all source pixels covered by a cell.
+    let colors = resize_pixels(frame, width, height, imgproc::INTER_AREA)
+        .wrap_err("failed to resize color frame")?;
+
+    let cells = match settings.mode {
+        RenderMode::Ramp => {
+            let glyphs: Vec<char> = if settings.dither == Dither::None {
+                luminance
+                    .iter()
+                    .map(|&value| settings.ramp.glyph(value))
+                    .collect()
+            } else {
+                dither(&luminance, cols, settings.ramp.len(), settings.dither)
+                    .into_iter()
+                    .map(|level| settings.ramp.level_glyph(level))
+                    .collect()
+            };
+            let edges = detect_edges(&small_gray, settings.edges, settings.edge_threshold)
+                .wrap_err("failed to detect edges")?;
+            let glyphs = glyphs
+                .into_iter()
+                .zip(edges)
+                .map(|(glyph, edge)| edge.unwrap_or(glyph));
+            glyph_cells(glyphs, &luminance, &colors)
+        }
+        RenderMode::Braille => {
+            let pixels = resize_pixels(gray, width * 2, height * 4, imgproc::INTER_AREA)
+                .wrap_err("failed to resize frame for Braille")?;
+            let glyphs = braille_glyphs(&pixels, cols, rows, settings);
+            glyph_cells(glyphs, &luminance, &colors)
+        }
+        RenderMode::HalfBlock => {
+            let pixels = resize_colors(frame, width, height * 2, adjustment)?;
+            block_cells(blocks::half_blocks(&pixels, cols, rows), &luminance)
+        }
+        RenderMode::Quadrant => {
+            let pixels = resize_colors(frame, width * 2, height * 2, adjustment)?;
+            block_cells(blocks::quadrants(&pixels, cols, rows), &luminance)
+        }
+        RenderMode::Sextant => {
+            let pixels = resize_colors(frame, width * 2, height * 3, adjustment)?;
+            block_cells(blocks::sextants(&pixels, cols, rows), &luminance)
+        }
+        RenderMode::Shape => {
+            let mut pixels = resize_pixels(gray, width * 2, height * 4, imgproc::INTER_AREA)
+                .wrap_err("failed to resize frame for shape matching")?;
+            if settings.ramp.is_inverted() {
+                pixels.iter_mut().for_each(|value| *value = 255 - *value);
+            }
+            let glyphs = shape::encode(&pixels, cols, rows);
+            glyph_cells(glyphs, &luminance, &colors)
+        }
+    };
+
+    Ok(AsciiFrame::from_cells(cols, rows, cells))
+}
 
+/// Resizes an image with the given interpolation.
+fn resize(image: &Mat, width: i32, height: i32, interpolation: i32) -> Result<Mat> {
     let mut resized = Mat::default();
     imgproc::resize(
-        &gray,
+        image,
         &mut resized,
         Size::new(width, height),
         0.0,
         0.0,
-        imgproc::INTER_LINEAR,
-    )
-    .wrap_err("failed to resize frame")?;
-
-    let (rows, cols) = (resized.rows(), resized.cols());
-
-    let ascii_frame = (0..rows)
-        .map(|y| {
-            (0..cols)
-                .map(|x| {
-                    let pixel = resized
-                        .at_2d::<u8>(y, x)
-                        .wrap_err("failed to access pixel")?;
-                    Ok(get_ascii_char(*pixel))
-                })
-                .collect::<Result<String>>()
+        interpolation,
+    )?;
+    Ok(resized)
+}
+
+/// Resizes an 8-bit image and returns its pixel bytes, row by row.
+fn resize_pixels(image: &Mat, width: i32, height: i32, interpolation: i32) -> Result<Vec<u8>> {
+    let resized = resize(image, width, height, interpolation)?;
+    Ok(resized
+        .data_bytes()
+        .wrap_err("failed to access pixels")?
+        .to_vec())
+}
+
+/// Resizes a BGR image with area interpolation and returns its pixel colors, row by row.
+///
+/// When the grayscale frame was adjusted, `adjustment` holds the grayscale frame
+/// before and after, and each color is shifted by the change of luminance of its
+/// pixel, so that the adjustments also apply to the block renderers.
+fn resize_colors(
+    frame: &Mat,
+    width: i32,
+    height: i32,
+    adjustment: Option<(&Mat, &Mat)>,
+) -> Result<Vec<Rgb>> {
+    let pixels = resize_pixels(frame, width, height, imgproc::INTER_AREA)
+        .wrap_err("failed to resize color frame for blocks")?;
+    let mut colors: Vec<Rgb> = pixels
+        .chunks_exact(3)
+        .map(|bgr| Rgb::new(bgr[2], bgr[1], bgr[0]))
+        .collect();
+
+    if let Some((original, adjusted)) = adjustment {
+        let before = resize_pixels(original, width, height, imgproc::INTER_AREA)
+            .wrap_err("failed to resize grayscale frame for blocks")?;
+        let after = resize_pixels(adjusted, width, height, imgproc::INTER_AREA)
+            .wrap_err("failed to resize adjusted frame for blocks")?;
+        for ((color, &before), &after) in colors.iter_mut().zip(&before).zip(&after) {
+            let shift = i16::from(after) - i16::from(before);
+            let channel = |value: u8| {
+                u8::try_from((i16::from(value) + shift).clamp(0, 255)).unwrap_or(u8::MAX)
+            };
+            *color = Rgb::new(channel(color.r), channel(color.g), channel(color.b));
+        }
+    }
+    Ok(colors)
+}
+
+/// Builds cells from glyphs, colored with the average BGR color of each cell.
+fn glyph_cells(
+    glyphs: impl IntoIterator<Item = char>,
+    luminance: &[u8],
+    colors: &[u8],
+) -> Vec<Cell> {
+    glyphs
+        .into_iter()
+        .zip(luminance)
+        .zip(colors.chunks_exact(3))
+        .map(|((glyph, &luminance), bgr)| {
+            Cell::new(glyph, luminance).with_fg(Rgb::new(bgr[2], bgr[1], bgr[0]))
         })
-        .collect::<Result<Vec<String>>>()?
-        .join("\n");
+        .collect()
+}
+
+/// Builds cells from rendered blocks, which carry their own colors.
+fn block_cells(blocks: Vec<BlockCell>, luminance: &[u8]) -> Vec<Cell> {
+    blocks
+        .into_iter()
+        .zip(luminance)
+        .map(|(block, &luminance)| {
+            Cell::new(block.glyph, luminance)
+                .with_fg(block.fg)
+                .with_bg(block.bg)
+        })
+        .collect()
+}
+
+/// Thresholds or dithers a `cols * 2` x `rows * 4` grayscale image and encodes it as Braille.
+fn braille_glyphs(pixels: &[u8], cols: usize, rows: usize, settings: &RenderSettings) -> Vec<char> {
+    let inverted = settings.ramp.is_inverted();
+
+    let lit: Vec<bool> = if settings.dither == Dither::None {
+        let mean = if pixels.is_empty() {
+            0
+        } else {
+            let sum: usize = pixels.iter().map(|&value| usize::from(value)).sum();
+            u8::try_from(sum / pixels.len()).unwrap_or(u8::MAX)
+        };
+        pixels
+            .iter()
+            .map(|&value| (value > mean) != inverted)
+            .collect()
+    } else {
+        dither(pixels, cols * 2, 2, settings.dither)
+            .into_iter()
+            .map(|level| (level == 1) != inverted)
+            .collect()
+    };
 
-    Ok(ascii_frame)
+    braille::encode(&lit, cols, rows)
 }
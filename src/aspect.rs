@@ -0,0 +1,175 @@
+//! # Aspect Ratio
+//!
+//! Terminal cells are roughly twice as tall as they are wide, so converting a
+//! frame to one cell per resized pixel stretches it vertically. This module
+//! works out how many cells a frame should cover to keep its proportions, and
+//! which part of the frame to keep when cropping.
+
+use crossterm::terminal;
+use opencv::core::Rect;
+use std::fmt;
+
+/// The width of a terminal cell divided by its height, used when the terminal
+/// does not report its size in pixels.
+pub const DEFAULT_CELL_ASPECT: f64 = 0.5;
+
+/// The range of cell aspect ratios that can be configured.
+pub const CELL_ASPECT_RANGE: (f64, f64) = (0.2, 2.0);
+
+/// How a frame is placed in the available cells.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum FitMode {
+    /// The whole frame is shown with its proportions, centered between blank bars.
+    #[default]
+    Letterbox,
+    /// The whole frame is shown with its proportions, using only the cells it needs.
+    Fit,
+    /// The frame covers all cells with its proportions, cropping its edges.
+    Fill,
+}
+
+impl FitMode {
+    /// All fit modes, in the order they are cycled through.
+    pub const ALL: [FitMode; 3] = [FitMode::Letterbox, FitMode::Fit, FitMode::Fill];
+
+    /// Returns the fit mode following this one, wrapping around at the end.
+    #[must_use]
+    pub fn next(self) -> FitMode {
+        let index = FitMode::ALL
+            .iter()
+            .position(|&mode| mode == self)
+            .unwrap_or(0);
+        FitMode::ALL[(index + 1) % FitMode::ALL.len()]
+    }
+}
+
+impl fmt::Display for FitMode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            FitMode::Letterbox => "letterbox",
+            FitMode::Fit => "fit",
+            FitMode::Fill => "fill",
+        };
+        f.write_str(name)
+    }
+}
+
+/// The part of a frame to convert and the number of cells it covers.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Placement {
+    /// The region of the frame, in pixels, to convert.
+    pub crop: Rect,
+    /// The width of the converted frame in cells.
+    pub width: i32,
+    /// The height of the converted frame in cells.
+    pub height: i32,
+}
+
+/// Returns the cell aspect ratio reported by the terminal.
+///
+/// # Returns
+///
+/// Returns the width of a cell divided by its height, or `None` if the terminal
+/// does not report its size in pixels.
+#[must_use]
+pub fn detect_cell_aspect() -> Option<f64> {
+    let size = terminal::window_size().ok()?;
+    if size.width == 0 || size.height == 0 || size.columns == 0 || size.rows == 0 {
+        return None;
+    }
+
+    let cell_width = f64::from(size.width) / f64::from(size.columns);
+    let cell_height = f64::from(size.height) / f64::from(size.rows);
+    let (low, high) = CELL_ASPECT_RANGE;
+    Some((cell_width / cell_height).clamp(low, high))
+}
+
+/// Places a frame in the available cells.
+///
+/// # Arguments
+///
+/// * `frame_width` - The width of the frame in pixels
+/// * `frame_height` - The height of the frame in pixels
+/// * `width` - The number of available cells per row
+/// * `height` - The number of available rows of cells
+/// * `cell_aspect` - The width of a cell divided by its height
+/// * `mode` - How to fit the frame into the cells
+///
+/// # Returns
+///
+/// Returns the region of the frame to convert and the number of cells it covers,
+/// which never exceeds the available cells.
+///
+/// # Examples
+///
+/// ```
+/// use ascii_webcam::aspect::{place, FitMode};
+/// // A 4:3 frame in 80x24 cells that are twice as tall as they are wide.
+/// let placement = place(640, 480, 80, 24, 0.5, FitMode::Letterbox);
+/// assert_eq!((placement.width, placement.height), (64, 24));
+/// ```
+#[must_use]
+pub fn place(
+    frame_width: i32,
+    frame_height: i32,
+    width: i32,
+    height: i32,
+    cell_aspect: f64,
+    mode: FitMode,
+) -> Placement {
+    let full = Rect::new(0, 0, frame_width, frame_height);
+    if frame_width <= 0 || frame_height <= 0 || width <= 0 || height <= 0 || cell_aspect <= 0.0 {
+        return Placement {
+            crop: full,
+            width: width.max(0),
+            height: height.max(0),
+        };
+    }
+
+    // The aspect ratios, width over height, of the frame and of the available cells.
+    let frame_aspect = f64::from(frame_width) / f64::from(frame_height);
+    let cells_aspect = f64::from(width) * cell_aspect / f64::from(height);
+
+    match mode {
+        FitMode::Letterbox | FitMode::Fit => {
+            let (fitted_width, fitted_height) = if frame_aspect > cells_aspect {
+                let rows = f64::from(width) * cell_aspect / frame_aspect;
+                (width, round_cells(rows, height))
+            } else {
+                let cols = f64::from(height) * frame_aspect / cell_aspect;
+                (round_cells(cols, width), height)
+            };
+            Placement {
+                crop: full,
+                width: fitted_width,
+                height: fitted_height,
+            }
+        }
+        FitMode::Fill => {
+            let crop = if frame_aspect > cells_aspect {
+                let crop_width = round_cells(f64::from(frame_height) * cells_aspect, frame_width);
+                Rect::new((frame_width - crop_width) / 2, 0, crop_width, frame_height)
+            } else {
+                let crop_height = round_cells(f64::from(frame_width) / cells_aspect, frame_height);
+                Rect::new(
+                    0,
+                    (frame_height - crop_height) / 2,
+                    frame_width,
+                    crop_height,
+                )
+            };
+            Placement {
+                crop,
+                width,
+                height,
+            }
+        }
+    }
+}
+
+/// Rounds a size to whole units, between `1` and `max`.
+// The size is clamped to the range of `i32` before the cast.
+#[allow(clippy::cast_possible_truncation)]
+fn round_cells(size: f64, max: i32) -> i32 {
+    size.round().clamp(1.0, f64::from(max)) as i32
+}
@@ -0,0 +1,216 @@
+//! # Block Rendering
+//!
+//! This module renders color pixels with Unicode block elements. Each cell
+//! covers several pixels and is drawn with a foreground and a background
+//! color:
+//! - half blocks (`▀`) show two vertical pixels per cell
+//! - quadrants (`▘▝▖▗`…) show 2x2 pixels per cell
+//! - sextants (Unicode 13, U+1FB00 to U+1FB3B) show 2x3 pixels per cell
+//!
+//! Quadrants and sextants can only show two colors per cell, so the pixels
+//! are split into the two groups that best match their original colors.
+
+use crate::color::Rgb;
+
+/// The quadrant characters, indexed by a mask of the lit quadrants
+/// (top-left `1`, top-right `2`, bottom-left `4`, bottom-right `8`).
+const QUADRANTS: [char; 16] = [
+    ' ', '▘', '▝', '▀', '▖', '▌', '▞', '▛', '▗', '▚', '▐', '▜', '▄', '▙', '▟', '█',
+];
+
+/// The first code point of the Unicode sextant characters.
+const SEXTANT_BASE: u32 = 0x1FB00;
+
+/// A rendered cell with its glyph, foreground and background colors.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BlockCell {
+    pub glyph: char,
+    pub fg: Rgb,
+    pub bg: Rgb,
+}
+
+/// Returns the quadrant character for a mask of lit quadrants.
+///
+/// # Examples
+///
+/// ```
+/// use ascii_webcam::blocks::quadrant_char;
+/// assert_eq!(quadrant_char(0b0001), '▘');
+/// assert_eq!(quadrant_char(0b1111), '█');
+/// ```
+#[must_use]
+pub fn quadrant_char(mask: u8) -> char {
+    QUADRANTS[usize::from(mask & 0x0F)]
+}
+
+/// Returns the sextant character for a mask of lit sextants.
+///
+/// The bits of the mask are, row by row: top-left `1`, top-right `2`,
+/// middle-left `4`, middle-right `8`, bottom-left `16` and bottom-right `32`.
+/// The four patterns that already exist as block elements (empty, full,
+/// left half and right half) are not part of the sextant range.
+///
+/// # Examples
+///
+/// ```
+/// use ascii_webcam::blocks::sextant_char;
+/// assert_eq!(sextant_char(0b000001), '\u{1FB00}');
+/// assert_eq!(sextant_char(0b010101), '▌');
+/// assert_eq!(sextant_char(0b111110), '\u{1FB3B}');
+/// ```
+#[must_use]
+pub fn sextant_char(mask: u8) -> char {
+    let mask = u32::from(mask & 0x3F);
+    match mask {
+        0 => ' ',
+        21 => '▌',
+        42 => '▐',
+        63 => '█',
+        _ => {
+            let skipped = u32::from(mask > 21) + u32::from(mask > 42);
+            char::from_u32(SEXTANT_BASE + mask - 1 - skipped).unwrap_or('█')
+        }
+    }
+}
+
+/// Renders pixels as half blocks, two vertical pixels per cell.
+///
+/// # Arguments
+///
+/// * `pixels` - The pixels, row by row, of a `cols` x `rows * 2` image
+/// * `cols` - The number of cells per row
+/// * `rows` - The number of rows of cells
+///
+/// # Panics
+///
+/// Panics if `pixels` holds fewer than `cols * rows * 2` pixels.
+#[must_use]
+pub fn half_blocks(pixels: &[Rgb], cols: usize, rows: usize) -> Vec<BlockCell> {
+    (0..rows)
+        .flat_map(|y| (0..cols).map(move |x| (x, y)))
+        .map(|(x, y)| BlockCell {
+            glyph: '▀',
+            fg: pixels[(y * 2) * cols + x],
+            bg: pixels[(y * 2 + 1) * cols + x],
+        })
+        .collect()
+}
+
+/// Renders pixels as quadrant blocks, 2x2 pixels per cell.
+///
+/// # Arguments
+///
+/// * `pixels` - The pixels, row by row, of a `cols * 2` x `rows * 2` image
+/// * `cols` - The number of cells per row
+/// * `rows` - The number of rows of cells
+///
+/// # Panics
+///
+/// Panics if `pixels` holds fewer than `cols * 2 * rows * 2` pixels.
+#[must_use]
+pub fn quadrants(pixels: &[Rgb], cols: usize, rows: usize) -> Vec<BlockCell> {
+    split_blocks(pixels, cols, rows, 2, quadrant_char)
+}
+
+/// Renders pixels as sextant blocks, 2x3 pixels per cell.
+///
+/// # Arguments
+///
+/// * `pixels` - The pixels, row by row, of a `cols * 2` x `rows * 3` image
+/// * `cols` - The number of cells per row
+/// * `rows` - The number of rows of cells
+///
+/// # Panics
+///
+/// Panics if `pixels` holds fewer than `cols * 2 * rows * 3` pixels.
+#[must_use]
+pub fn sextants(pixels: &[Rgb], cols: usize, rows: usize) -> Vec<BlockCell> {
+    split_blocks(pixels, cols, rows, 3, sextant_char)
+}
+
+/// Renders blocks of 2 x `block_height` pixels with the best two-color split per cell.
+fn split_blocks(
+    pixels: &[Rgb],
+    cols: usize,
+    rows: usize,
+    block_height: usize,
+    glyph: fn(u8) -> char,
+) -> Vec<BlockCell> {
+    let pixel_width = cols * 2;
+    let mut block = Vec::with_capacity(2 * block_height);
+
+    (0..rows)
+        .flat_map(|y| (0..cols).map(move |x| (x, y)))
+        .map(|(x, y)| {
+            block.clear();
+            for row in 0..block_height {
+                let start = (y * block_height + row) * pixel_width + x * 2;
+                block.extend_from_slice(&pixels[start..start + 2]);
+            }
+
+            let (mask, fg, bg) = best_split(&block);
+            BlockCell {
+                glyph: glyph(mask),
+                fg,
+                bg,
+            }
+        })
+        .collect()
+}
+
+/// Finds the split of `block` into two groups with the smallest color error.
+///
+/// Returns the mask of the pixels in the foreground group, along with the mean
+/// colors of the foreground and background groups.
+fn best_split(block: &[Rgb]) -> (u8, Rgb, Rgb) {
+    let count = block.len();
+    let full = (1u8 << count) - 1;
+
+    // A mask and its complement describe the same split, so only the masks with
+    // the highest bit set are tried. The full mask (a single color) is tried first
+    // so that it wins ties, e.g. for uniform blocks.
+    let mut best = (full, Rgb::default(), Rgb::default());
+    let mut best_error = u32::MAX;
+
+    for mask in ((1u8 << (count - 1))..=full).rev() {
+        let fg = mean(block, mask);
+        let bg = if mask == full {
+            fg
+        } else {
+            mean(block, !mask & full)
+        };
+
+        let error = block
+            .iter()
+            .enumerate()
+            .map(|(i, pixel)| {
+                let target = if mask & (1 << i) != 0 { fg } else { bg };
+                pixel.distance(target)
+            })
+            .sum();
+
+        if error < best_error {
+            best_error = error;
+            best = (mask, fg, bg);
+        }
+    }
+
+    best
+}
+
+/// Returns the mean color of the pixels of `block` selected by `mask`.
+fn mean(block: &[Rgb], mask: u8) -> Rgb {
+    let (mut r, mut g, mut b, mut n) = (0u32, 0u32, 0u32, 0u32);
+    for (i, pixel) in block.iter().enumerate() {
+        if mask & (1 << i) != 0 {
+            r += u32::from(pixel.r);
+            g += u32::from(pixel.g);
+            b += u32::from(pixel.b);
+            n += 1;
+        }
+    }
+
+    let n = n.max(1);
+    let channel = |sum: u32| u8::try_from(sum / n).unwrap_or(u8::MAX);
+    Rgb::new(channel(r), channel(g), channel(b))
+}
@@ -0,0 +1,74 @@
+//! # Braille Rendering
+//!
+//! This module encodes 2x4 pixel blocks as Unicode Braille patterns
+//! (U+2800 to U+28FF), giving each terminal cell eight "pixels".
+
+/// The first code point of the Unicode Braille Patterns block.
+const BRAILLE_BASE: u32 = 0x2800;
+
+/// The bit of each dot of a Braille cell, indexed by `[row][column]`.
+const DOT_BITS: [[u8; 2]; 4] = [[0x01, 0x08], [0x02, 0x10], [0x04, 0x20], [0x40, 0x80]];
+
+/// Returns the Braille character with the given raised dots.
+///
+/// # Examples
+///
+/// ```
+/// use ascii_webcam::braille::braille_char;
+/// assert_eq!(braille_char(0x00), '⠀');
+/// assert_eq!(braille_char(0xFF), '⣿');
+/// ```
+#[must_use]
+pub fn braille_char(dots: u8) -> char {
+    char::from_u32(BRAILLE_BASE + u32::from(dots)).unwrap_or(' ')
+}
+
+/// Encodes a bitmap of lit pixels as Braille characters.
+///
+/// # Arguments
+///
+/// * `lit` - The bitmap, laid out row by row, of `width * 2` x `height * 4` pixels
+/// * `width` - The number of Braille cells per row
+/// * `height` - The number of rows of Braille cells
+///
+/// # Returns
+///
+/// Returns the `width * height` Braille characters, row by row.
+///
+/// # Panics
+///
+/// Panics if `lit` holds fewer than `width * 2 * height * 4` pixels.
+///
+/// # Examples
+///
+/// ```
+/// use ascii_webcam::braille::encode;
+/// // A single cell with only its left column lit.
+/// let lit = [true, false, true, false, true, false, true, false];
+/// assert_eq!(encode(&lit, 1, 1), vec!['⡇']);
+/// ```
+#[must_use]
+pub fn encode(lit: &[bool], width: usize, height: usize) -> Vec<char> {
+    let pixel_width = width * 2;
+    assert!(
+        lit.len() >= pixel_width * height * 4,
+        "bitmap is smaller than the Braille grid"
+    );
+
+    (0..height)
+        .flat_map(|cell_y| (0..width).map(move |cell_x| (cell_x, cell_y)))
+        .map(|(cell_x, cell_y)| {
+            let mut dots = 0;
+            for (row, bits) in DOT_BITS.iter().enumerate() {
+                for (column, bit) in bits.iter().enumerate() {
+                    let x = cell_x * 2 + column;
+                    let y = cell_y * 4 + row;
+                    if lit[y * pixel_width + x] {
+                        dots |= bit;
+                    }
+                }
+            }
+            braille_char(dots)
+        })
+        .collect()
+}
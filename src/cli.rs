@@ -0,0 +1,404 @@
+//! # Command-Line Interface
+//!
+//! This module defines the arguments of the `ascii-webcam` binary and turns them
+//! into the settings of the application. Values clap cannot check on its own,
+//! such as sizes, FOURCC codes and custom ramps, are validated here. The binary
+//! reports all invalid arguments as `AppError::Config` errors.
+
+use crate::app::App;
+use crate::ascii::{RenderMode, RenderSettings};
+use crate::aspect::FitMode;
+use crate::color::ColorMode;
+use crate::dither::Dither;
+use crate::edges::EdgeMode;
+use crate::error::Result;
+use crate::ramp::{CharRamp, RampPreset};
+use crate::snapshot::SnapshotFormat;
+use crate::video::{fourcc_code, CaptureFormat};
+use clap::builder::{PossibleValuesParser, TypedValueParser};
+use clap::{ArgAction, ArgGroup, CommandFactory, Parser, ValueEnum};
+use clap_complete::Shell;
+use std::fmt;
+use std::io::Write;
+use std::path::PathBuf;
+
+/// The color depth chosen on the command line.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum ColorChoice {
+    /// The best color mode the terminal supports.
+    Auto,
+    /// Plain characters without any color.
+    Mono,
+    /// The 16 basic ANSI colors.
+    #[value(name = "16")]
+    Ansi16,
+    /// The xterm-256 palette.
+    #[value(name = "256")]
+    Ansi256,
+    /// 24-bit RGB colors.
+    #[value(name = "truecolor")]
+    TrueColor,
+}
+
+impl ColorChoice {
+    /// Returns the color mode to render with, detecting the terminal for `Auto`.
+    #[must_use]
+    pub fn color_mode(self) -> ColorMode {
+        match self {
+            ColorChoice::Auto => ColorMode::detect(),
+            ColorChoice::Mono => ColorMode::Mono,
+            ColorChoice::Ansi16 => ColorMode::Ansi16,
+            ColorChoice::Ansi256 => ColorMode::Ansi256,
+            ColorChoice::TrueColor => ColorMode::TrueColor,
+        }
+    }
+}
+
+/// Shows a webcam, video, images or piped frames as ASCII art in the terminal.
+#[derive(Debug, Clone, Parser)]
+#[command(name = "ascii-webcam", version, about)]
+#[command(group(ArgGroup::new("output_mode").args(["headless", "snapshot"])))]
+#[allow(clippy::struct_excessive_bools)]
+pub struct Cli {
+    /// What to show instead of the camera: a video file, an image, a directory or
+    /// glob pattern of images, `synthetic[:PATTERN]`, `-` for a YUV4MPEG2 stream
+    /// on stdin, or `raw:WxH[@FPS][:FILE]` for raw BGR24 frames
+    #[arg(value_name = "SOURCE", conflicts_with = "camera")]
+    pub source: Option<PathBuf>,
+
+    /// The index of the camera to open [default: 0]
+    #[arg(short, long, value_name = "INDEX")]
+    pub camera: Option<i32>,
+
+    /// The configuration file [default: $XDG_CONFIG_HOME/ascii-webcam/config.toml]
+    #[arg(long, value_name = "FILE")]
+    pub config: Option<PathBuf>,
+
+    /// List the cameras and the resolutions they support, then exit
+    #[arg(long)]
+    pub list_devices: bool,
+
+    /// The resolution requested from the camera
+    #[arg(long, value_name = "WxH", value_parser = parse_size)]
+    pub resolution: Option<(u16, u16)>,
+
+    /// The frame rate requested from the camera
+    #[arg(long, value_name = "FPS", value_parser = parse_fps)]
+    pub capture_fps: Option<f64>,
+
+    /// The pixel format requested from the camera, such as MJPG or YUYV
+    #[arg(long, value_name = "CODE")]
+    pub fourcc: Option<String>,
+
+    /// How frames are turned into characters
+    #[arg(short = 'm', long, value_name = "MODE", value_parser = named(&RenderMode::ALL))]
+    pub renderer: Option<RenderMode>,
+
+    /// The built-in character ramp
+    #[arg(short, long, value_name = "PRESET", value_parser = named(&RampPreset::ALL))]
+    pub ramp: Option<RampPreset>,
+
+    /// A custom character ramp, ordered from darkest to brightest
+    #[arg(long, value_name = "GLYPHS", conflicts_with = "ramp")]
+    pub glyphs: Option<String>,
+
+    /// Invert the character ramp, for terminals with a light background
+    #[arg(short, long, overrides_with = "no_invert")]
+    pub invert: bool,
+
+    /// Keep the character ramp as it is, even if the configuration file inverts it
+    #[arg(long, overrides_with = "invert")]
+    pub no_invert: bool,
+
+    /// The color depth [default: mono, or auto for the block renderers]
+    #[arg(long, value_name = "MODE")]
+    pub color: Option<ColorChoice>,
+
+    /// The dithering method
+    #[arg(long, value_name = "METHOD", value_parser = named(&Dither::ALL))]
+    pub dither: Option<Dither>,
+
+    /// The edge detector
+    #[arg(long, value_name = "DETECTOR", value_parser = named(&EdgeMode::ALL))]
+    pub edges: Option<EdgeMode>,
+
+    /// How frames are placed in the available cells
+    #[arg(long, value_name = "MODE", value_parser = named(&FitMode::ALL))]
+    pub fit: Option<FitMode>,
+
+    /// Flip frames horizontally, like a mirror
+    #[arg(long, overrides_with = "no_mirror")]
+    pub mirror: bool,
+
+    /// Show frames as they are, even if the configuration file mirrors them
+    #[arg(long, overrides_with = "mirror")]
+    pub no_mirror: bool,
+
+    /// Hide the Stats block above the frame
+    #[arg(long, overrides_with = "stats")]
+    pub no_stats: bool,
+
+    /// Show the Stats block, even if the configuration file hides it
+    #[arg(long, overrides_with = "no_stats")]
+    pub stats: bool,
+
+    /// Hide the instructions and playback progress below the frame
+    #[arg(long, overrides_with = "status")]
+    pub no_status: bool,
+
+    /// Show the instructions and playback progress, even if the configuration file
+    /// hides them
+    #[arg(long, overrides_with = "no_status")]
+    pub status: bool,
+
+    /// The maximum number of frames drawn per second [default: the frame rate of
+    /// the source]
+    #[arg(long, value_name = "FPS", value_parser = parse_fps)]
+    pub fps: Option<f64>,
+
+    /// The maximum size of the ASCII frame in cells [default: the terminal size,
+    /// or 80x24 when headless frames are not written to a terminal]
+    #[arg(short, long, value_name = "COLSxROWS", value_parser = parse_size)]
+    pub size: Option<(u16, u16)>,
+
+    /// Stream frames to stdout or a file instead of showing the terminal interface:
+    /// drawn in place on a terminal, separated by form feeds otherwise
+    #[arg(long)]
+    pub headless: bool,
+
+    /// Write a single frame, then exit
+    #[arg(long)]
+    pub snapshot: bool,
+
+    /// The number of frames to skip before the snapshot, to let auto-exposure and
+    /// the camera settle
+    #[arg(
+        long,
+        value_name = "FRAMES",
+        default_value_t = 0,
+        requires = "snapshot"
+    )]
+    pub warmup: u32,
+
+    /// The format of the snapshot [default: from the extension of the output file,
+    /// or ansi with colors and text without]
+    #[arg(
+        long,
+        value_name = "FORMAT",
+        value_parser = named(&SnapshotFormat::ALL),
+        requires = "snapshot"
+    )]
+    pub format: Option<SnapshotFormat>,
+
+    /// The file headless frames or the snapshot are written to, or `-` for stdout
+    #[arg(short, long, value_name = "FILE", requires = "output_mode")]
+    pub output: Option<PathBuf>,
+
+    /// Print the source and capture mode on startup; twice to also show backtraces
+    /// on errors
+    #[arg(short, long, action = ArgAction::Count)]
+    pub verbose: u8,
+
+    /// Print the completion script for a shell, then exit
+    #[arg(long, value_name = "SHELL")]
+    pub completions: Option<Shell>,
+}
+
+impl Cli {
+    /// Returns the capture mode to request from cameras.
+    ///
+    /// # Errors
+    ///
+    /// This function returns an `AppError::Config` error if the FOURCC code is not
+    /// made of four ASCII characters.
+    pub fn capture_format(&self) -> Result<CaptureFormat> {
+        if let Some(fourcc) = &self.fourcc {
+            fourcc_code(fourcc)?;
+        }
+        Ok(CaptureFormat {
+            width: self.resolution.map(|(width, _)| i32::from(width)),
+            height: self.resolution.map(|(_, height)| i32::from(height)),
+            fps: self.capture_fps,
+            fourcc: self.fourcc.clone(),
+        })
+    }
+
+    /// Returns the render settings chosen on the command line, with defaults for
+    /// the others.
+    ///
+    /// # Errors
+    ///
+    /// This function returns an `AppError::Config` error if the custom ramp has
+    /// fewer than two glyphs.
+    pub fn render_settings(&self) -> Result<RenderSettings> {
+        let mut ramp = match (&self.glyphs, self.ramp) {
+            (Some(glyphs), _) => CharRamp::new(glyphs)?,
+            (None, Some(preset)) => CharRamp::preset(preset),
+            (None, None) => CharRamp::default(),
+        };
+        if self.invert {
+            ramp.invert();
+        }
+        let defaults = RenderSettings::default();
+        Ok(RenderSettings {
+            mode: self.renderer.unwrap_or(defaults.mode),
+            ramp,
+            dither: self.dither.unwrap_or(defaults.dither),
+            edges: self.edges.unwrap_or(defaults.edges),
+            ..defaults
+        })
+    }
+
+    /// Applies the display settings chosen on the command line to the application.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use ascii_webcam::app::App;
+    /// use ascii_webcam::ascii::RenderMode;
+    /// use ascii_webcam::cli::Cli;
+    /// use clap::Parser;
+    /// let cli = Cli::try_parse_from(["ascii-webcam", "--renderer", "braille", "--mirror"]).unwrap();
+    /// let mut app = App::new();
+    /// cli.apply(&mut app).unwrap();
+    /// assert_eq!(app.settings.mode, RenderMode::Braille);
+    /// assert!(app.mirror);
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// This function returns an `AppError::Config` error if a setting is invalid.
+    pub fn apply(&self, app: &mut App) -> Result<()> {
+        app.settings = self.render_settings()?;
+        app.color_mode = self.color_mode(app.settings.mode);
+        app.fit = self.fit.unwrap_or_default();
+        app.mirror = self.mirror;
+        app.show_stats = !self.no_stats;
+        app.show_status = !self.no_status;
+        Ok(())
+    }
+
+    /// Applies the display settings that differ from `previous` to the application.
+    ///
+    /// Settings changed with keys since, such as the render mode or the colors, are
+    /// kept unless these options change them too, so that a configuration file
+    /// reloaded for an unrelated change does not reset them.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use ascii_webcam::app::App;
+    /// use ascii_webcam::ascii::RenderMode;
+    /// use ascii_webcam::cli::Cli;
+    /// use clap::Parser;
+    /// let previous = Cli::try_parse_from(["ascii-webcam"]).unwrap();
+    /// let mut app = App::new();
+    /// previous.apply(&mut app).unwrap();
+    /// app.next_render_mode();
+    ///
+    /// let cli = Cli::try_parse_from(["ascii-webcam", "--mirror"]).unwrap();
+    /// cli.apply_changes(&previous, &mut app).unwrap();
+    /// assert_eq!(app.settings.mode, RenderMode::Braille);
+    /// assert!(app.mirror);
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// This function returns an `AppError::Config` error if a setting is invalid.
+    pub fn apply_changes(&self, previous: &Cli, app: &mut App) -> Result<()> {
+        let settings = self.render_settings()?;
+        let renderer_changed = self.renderer != previous.renderer;
+        if renderer_changed {
+            app.settings.mode = settings.mode;
+        }
+        if (self.ramp, &self.glyphs, self.invert)
+            != (previous.ramp, &previous.glyphs, previous.invert)
+        {
+            app.settings.ramp = settings.ramp;
+        }
+        if self.dither != previous.dither {
+            app.settings.dither = settings.dither;
+        }
+        if self.edges != previous.edges {
+            app.settings.edges = settings.edges;
+        }
+        // Without a color setting, the default colors follow the renderer.
+        if self.color != previous.color || (self.color.is_none() && renderer_changed) {
+            app.color_mode = self.color_mode(app.settings.mode);
+        }
+        if self.fit != previous.fit {
+            app.fit = self.fit.unwrap_or_default();
+        }
+        if self.mirror != previous.mirror {
+            app.mirror = self.mirror;
+        }
+        if self.no_stats != previous.no_stats {
+            app.show_stats = !self.no_stats;
+        }
+        if self.no_status != previous.no_status {
+            app.show_status = !self.no_status;
+        }
+        Ok(())
+    }
+
+    /// Returns the color mode chosen, or the default one for a render mode.
+    fn color_mode(&self, mode: RenderMode) -> ColorMode {
+        // Block renderers cannot show the image without colors.
+        match self.color {
+            Some(color) => color.color_mode(),
+            None if mode.needs_color() => ColorMode::detect(),
+            None => ColorMode::Mono,
+        }
+    }
+
+    /// Writes the completion script of the command for a shell.
+    pub fn write_completions(shell: Shell, output: &mut impl Write) {
+        let mut command = Cli::command();
+        let name = command.get_name().to_string();
+        clap_complete::generate(shell, &mut command, name, output);
+    }
+}
+
+/// Parses a value among the displayed names of a cycled setting, listing them in
+/// the help and completions.
+fn named<T>(values: &'static [T]) -> impl TypedValueParser<Value = T>
+where
+    T: Copy + fmt::Display + Send + Sync + 'static,
+{
+    PossibleValuesParser::new(values.iter().map(ToString::to_string)).map(move |name| {
+        values
+            .iter()
+            .copied()
+            .find(|value| value.to_string() == name)
+            .unwrap_or(values[0])
+    })
+}
+
+/// Parses a size in the form `WxH`, such as `1280x720`.
+pub(crate) fn parse_size(value: &str) -> std::result::Result<(u16, u16), String> {
+    let invalid = || "expected WxH, such as 640x480".to_string();
+    let (width, height) = value.split_once(['x', 'X']).ok_or_else(invalid)?;
+    let width = width.parse::<u16>().map_err(|_| invalid())?;
+    let height = height.parse::<u16>().map_err(|_| invalid())?;
+    if width == 0 || height == 0 {
+        return Err("sizes must not be empty".to_string());
+    }
+    Ok((width, height))
+}
+
+/// Parses a positive, finite number of frames per second.
+fn parse_fps(value: &str) -> std::result::Result<f64, String> {
+    value
+        .parse::<f64>()
+        .map_err(|err| err.to_string())
+        .and_then(check_fps)
+}
+
+/// Checks that a number of frames per second is positive and finite.
+pub(crate) fn check_fps(fps: f64) -> std::result::Result<f64, String> {
+    if fps.is_finite() && fps > 0.0 {
+        Ok(fps)
+    } else {
+        Err("expected a positive number of frames per second".to_string())
+    }
+}
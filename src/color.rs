@@ -0,0 +1,275 @@
+//! # Color Handling
+//!
+//! This module provides the color modes supported by the ASCII Webcam
+//! application, terminal capability detection, and conversion of 24-bit
+//! colors to the xterm-256 and 16-color palettes.
+
+use ratatui::style::Color;
+use std::{env, fmt};
+
+/// The standard xterm RGB values of the 16 basic ANSI colors.
+const ANSI16_PALETTE: [(u8, u8, u8); 16] = [
+    (0, 0, 0),
+    (205, 0, 0),
+    (0, 205, 0),
+    (205, 205, 0),
+    (0, 0, 238),
+    (205, 0, 205),
+    (0, 205, 205),
+    (229, 229, 229),
+    (127, 127, 127),
+    (255, 0, 0),
+    (0, 255, 0),
+    (255, 255, 0),
+    (92, 92, 255),
+    (255, 0, 255),
+    (0, 255, 255),
+    (255, 255, 255),
+];
+
+/// The channel intensities used by the 6x6x6 color cube of the xterm-256 palette.
+const CUBE_LEVELS: [u8; 6] = [0, 95, 135, 175, 215, 255];
+
+/// A 24-bit RGB color.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Rgb {
+    pub r: u8,
+    pub g: u8,
+    pub b: u8,
+}
+
+impl Rgb {
+    /// Creates a new `Rgb` color.
+    #[must_use]
+    pub const fn new(r: u8, g: u8, b: u8) -> Self {
+        Rgb { r, g, b }
+    }
+
+    /// Returns the perceived brightness of the color (0-255).
+    ///
+    /// Uses the same Rec. 601 weights as `OpenCV`'s `COLOR_BGR2GRAY` conversion,
+    /// so colored and grayscale rendering pick the same characters.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use ascii_webcam::color::Rgb;
+    /// assert_eq!(Rgb::new(0, 0, 0).luminance(), 0);
+    /// assert_eq!(Rgb::new(255, 255, 255).luminance(), 255);
+    /// ```
+    #[must_use]
+    pub fn luminance(self) -> u8 {
+        let value =
+            0.299 * f64::from(self.r) + 0.587 * f64::from(self.g) + 0.114 * f64::from(self.b);
+        // The weights sum to 1.0, so the result always fits in a u8.
+        #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+        let value = value.round() as u8;
+        value
+    }
+
+    /// Returns the squared euclidean distance between two colors.
+    #[must_use]
+    pub fn distance(self, other: Rgb) -> u32 {
+        let dr = i32::from(self.r) - i32::from(other.r);
+        let dg = i32::from(self.g) - i32::from(other.g);
+        let db = i32::from(self.b) - i32::from(other.b);
+        (dr * dr + dg * dg + db * db).unsigned_abs()
+    }
+}
+
+/// Maps a 24-bit color to the closest entry of the xterm-256 palette.
+///
+/// Both the 6x6x6 color cube (indices 16-231) and the grayscale ramp
+/// (indices 232-255) are considered, and the nearer of the two is returned.
+///
+/// # Examples
+///
+/// ```
+/// use ascii_webcam::color::{rgb_to_ansi256, Rgb};
+/// assert_eq!(rgb_to_ansi256(Rgb::new(255, 0, 0)), 196);
+/// assert_eq!(rgb_to_ansi256(Rgb::new(128, 128, 128)), 244);
+/// ```
+#[must_use]
+pub fn rgb_to_ansi256(color: Rgb) -> u8 {
+    let cube_index = |value: u8| -> u8 {
+        let (index, _) = CUBE_LEVELS
+            .iter()
+            .enumerate()
+            .min_by_key(|(_, &level)| level.abs_diff(value))
+            .unwrap_or((0, &0));
+        // There are only six cube levels.
+        #[allow(clippy::cast_possible_truncation)]
+        let index = index as u8;
+        index
+    };
+
+    let (ri, gi, bi) = (
+        cube_index(color.r),
+        cube_index(color.g),
+        cube_index(color.b),
+    );
+    let cube = Rgb::new(
+        CUBE_LEVELS[usize::from(ri)],
+        CUBE_LEVELS[usize::from(gi)],
+        CUBE_LEVELS[usize::from(bi)],
+    );
+
+    let average = (u16::from(color.r) + u16::from(color.g) + u16::from(color.b)) / 3;
+    // The grayscale ramp runs from 8 to 238 in steps of 10.
+    #[allow(clippy::cast_possible_truncation)]
+    let gray_index = (average.saturating_sub(3) / 10).min(23) as u8;
+    let gray_level = 8 + gray_index * 10;
+    let gray = Rgb::new(gray_level, gray_level, gray_level);
+
+    if color.distance(gray) < color.distance(cube) {
+        232 + gray_index
+    } else {
+        16 + 36 * ri + 6 * gi + bi
+    }
+}
+
+/// Maps a 24-bit color to the closest of the 16 basic ANSI colors.
+///
+/// # Examples
+///
+/// ```
+/// use ascii_webcam::color::{rgb_to_ansi16, Rgb};
+/// assert_eq!(rgb_to_ansi16(Rgb::new(0, 0, 0)), 0);
+/// assert_eq!(rgb_to_ansi16(Rgb::new(250, 250, 250)), 15);
+/// ```
+#[must_use]
+pub fn rgb_to_ansi16(color: Rgb) -> u8 {
+    let (index, _) = ANSI16_PALETTE
+        .iter()
+        .enumerate()
+        .min_by_key(|(_, &(r, g, b))| color.distance(Rgb::new(r, g, b)))
+        .unwrap_or((0, &(0, 0, 0)));
+    // The palette has only sixteen entries.
+    #[allow(clippy::cast_possible_truncation)]
+    let index = index as u8;
+    index
+}
+
+/// The color depth used when rendering ASCII frames.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ColorMode {
+    /// Plain characters without any color.
+    #[default]
+    Mono,
+    /// The 16 basic ANSI colors.
+    Ansi16,
+    /// The xterm-256 palette.
+    Ansi256,
+    /// 24-bit RGB colors.
+    TrueColor,
+}
+
+impl ColorMode {
+    /// Detects the best color mode supported by the current terminal.
+    ///
+    /// The detection follows the usual conventions:
+    /// - `COLORTERM=truecolor` or `COLORTERM=24bit` selects 24-bit colors
+    /// - a `TERM` containing `256color` selects the xterm-256 palette
+    /// - anything else falls back to the 16 basic ANSI colors
+    #[must_use]
+    pub fn detect() -> Self {
+        let colorterm = env::var("COLORTERM").unwrap_or_default();
+        if colorterm.eq_ignore_ascii_case("truecolor") || colorterm.eq_ignore_ascii_case("24bit") {
+            return ColorMode::TrueColor;
+        }
+
+        if env::var("TERM").is_ok_and(|term| term.contains("256color")) {
+            ColorMode::Ansi256
+        } else {
+            ColorMode::Ansi16
+        }
+    }
+
+    /// Converts a 24-bit color to the closest `ratatui` color for this mode.
+    ///
+    /// Returns `None` in `Mono` mode.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use ascii_webcam::color::{ColorMode, Rgb};
+    /// use ratatui::style::Color;
+    ///
+    /// let red = Rgb::new(255, 0, 0);
+    /// assert_eq!(ColorMode::Mono.to_color(red), None);
+    /// assert_eq!(ColorMode::TrueColor.to_color(red), Some(Color::Rgb(255, 0, 0)));
+    /// assert_eq!(ColorMode::Ansi256.to_color(red), Some(Color::Indexed(196)));
+    /// assert_eq!(ColorMode::Ansi16.to_color(red), Some(Color::LightRed));
+    /// ```
+    #[must_use]
+    pub fn to_color(self, color: Rgb) -> Option<Color> {
+        match self {
+            ColorMode::Mono => None,
+            ColorMode::Ansi16 => Some(match rgb_to_ansi16(color) {
+                0 => Color::Black,
+                1 => Color::Red,
+                2 => Color::Green,
+                3 => Color::Yellow,
+                4 => Color::Blue,
+                5 => Color::Magenta,
+                6 => Color::Cyan,
+                7 => Color::Gray,
+                8 => Color::DarkGray,
+                9 => Color::LightRed,
+                10 => Color::LightGreen,
+                11 => Color::LightYellow,
+                12 => Color::LightBlue,
+                13 => Color::LightMagenta,
+                14 => Color::LightCyan,
+                _ => Color::White,
+            }),
+            ColorMode::Ansi256 => Some(Color::Indexed(rgb_to_ansi256(color))),
+            ColorMode::TrueColor => Some(Color::Rgb(color.r, color.g, color.b)),
+        }
+    }
+
+    /// Returns the parameters of the ANSI SGR escape sequence that sets a 24-bit
+    /// color as the foreground or background in this mode.
+    ///
+    /// Returns `None` in `Mono` mode.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use ascii_webcam::color::{ColorMode, Rgb};
+    ///
+    /// let red = Rgb::new(255, 0, 0);
+    /// assert_eq!(ColorMode::Mono.sgr(red, false), None);
+    /// assert_eq!(ColorMode::TrueColor.sgr(red, false).as_deref(), Some("38;2;255;0;0"));
+    /// assert_eq!(ColorMode::Ansi256.sgr(red, true).as_deref(), Some("48;5;196"));
+    /// assert_eq!(ColorMode::Ansi16.sgr(red, false).as_deref(), Some("91"));
+    /// ```
+    #[must_use]
+    pub fn sgr(self, color: Rgb, background: bool) -> Option<String> {
+        let layer = if background { 48 } else { 38 };
+        match self {
+            ColorMode::Mono => None,
+            ColorMode::Ansi16 => {
+                // The bright colors have their own codes, 90-97 and 100-107.
+                let index = rgb_to_ansi16(color);
+                let base = if index < 8 { 30 } else { 90 - 8 };
+                let offset = if background { 10 } else { 0 };
+                Some((base + offset + index).to_string())
+            }
+            ColorMode::Ansi256 => Some(format!("{layer};5;{}", rgb_to_ansi256(color))),
+            ColorMode::TrueColor => Some(format!("{layer};2;{};{};{}", color.r, color.g, color.b)),
+        }
+    }
+}
+
+impl fmt::Display for ColorMode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            ColorMode::Mono => "mono",
+            ColorMode::Ansi16 => "16 colors",
+            ColorMode::Ansi256 => "256 colors",
+            ColorMode::TrueColor => "truecolor",
+        };
+        f.write_str(name)
+    }
+}
@@ -0,0 +1,332 @@
+//! # Configuration File
+//!
+//! Settings can be stored in a TOML file instead of being passed on every run.
+//! The file is read from `--config`, or from `ascii-webcam/config.toml` in the
+//! XDG configuration directory, `~/.config` by default. Its keys are named after
+//! the long command-line options, which take precedence over them:
+//!
+//! ```toml
+//! source = "synthetic:box"
+//! renderer = "braille"
+//! ramp = "blocks"
+//! color = "auto"
+//! mirror = true
+//!
+//! [keys]
+//! quit = "x"
+//!
+//! [overlays]
+//! stats = false
+//! ```
+//!
+//! While the application runs, the file is watched with `ConfigWatcher`, and the
+//! display settings that changed are applied again when it is saved. The source
+//! and capture mode only change on restart.
+
+use crate::ascii::RenderMode;
+use crate::aspect::FitMode;
+use crate::cli::{check_fps, parse_size, Cli, ColorChoice};
+use crate::dither::Dither;
+use crate::edges::EdgeMode;
+use crate::error::{AppError, Result};
+use crate::keymap::Keymap;
+use crate::ramp::RampPreset;
+use clap::ValueEnum;
+use color_eyre::eyre::WrapErr;
+use crossbeam_channel::{unbounded, Receiver};
+use notify::{RecommendedWatcher, RecursiveMode, Watcher};
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use std::fmt;
+use std::path::{Path, PathBuf};
+
+/// The name of the directory of the configuration file in the XDG configuration directory.
+const CONFIG_DIR: &str = "ascii-webcam";
+/// The name of the configuration file.
+const CONFIG_FILE: &str = "config.toml";
+
+/// The settings read from a configuration file. Missing keys keep their defaults.
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields, rename_all = "kebab-case")]
+pub struct Config {
+    /// What to show instead of the camera, as the `SOURCE` argument.
+    pub source: Option<PathBuf>,
+    /// The index of the camera to open.
+    pub camera: Option<i32>,
+    /// The resolution requested from the camera, as `WxH`.
+    pub resolution: Option<String>,
+    /// The frame rate requested from the camera.
+    pub capture_fps: Option<f64>,
+    /// The pixel format requested from the camera.
+    pub fourcc: Option<String>,
+    /// The render mode.
+    pub renderer: Option<String>,
+    /// The built-in character ramp.
+    pub ramp: Option<String>,
+    /// A custom character ramp, which replaces `ramp`.
+    pub glyphs: Option<String>,
+    /// Whether the character ramp is inverted.
+    pub invert: Option<bool>,
+    /// The color depth: `auto`, `mono`, `16`, `256` or `truecolor`.
+    pub color: Option<String>,
+    /// The dithering method.
+    pub dither: Option<String>,
+    /// The edge detector.
+    pub edges: Option<String>,
+    /// How frames are placed in the available cells.
+    pub fit: Option<String>,
+    /// Whether frames are flipped horizontally.
+    pub mirror: Option<bool>,
+    /// The maximum number of frames drawn per second.
+    pub fps: Option<f64>,
+    /// The maximum size of the ASCII frame in cells, as `COLSxROWS`.
+    pub size: Option<String>,
+    /// The keys bound to actions, by action name.
+    pub keys: BTreeMap<String, String>,
+    /// The parts of the interface shown around the frame.
+    pub overlays: Overlays,
+}
+
+/// The parts of the interface shown around the frame.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Overlays {
+    /// Whether the Stats block is shown above the frame.
+    pub stats: bool,
+    /// Whether the instructions, or the playback progress, are shown below the frame.
+    pub status: bool,
+}
+
+impl Default for Overlays {
+    fn default() -> Self {
+        Overlays {
+            stats: true,
+            status: true,
+        }
+    }
+}
+
+impl Config {
+    /// Returns the path of the configuration file in the XDG configuration
+    /// directory, whether or not it exists.
+    #[must_use]
+    pub fn default_path() -> Option<PathBuf> {
+        let config_home = std::env::var_os("XDG_CONFIG_HOME")
+            .map(PathBuf::from)
+            .filter(|path| path.is_absolute())
+            .or_else(|| std::env::var_os("HOME").map(|home| PathBuf::from(home).join(".config")))?;
+        Some(config_home.join(CONFIG_DIR).join(CONFIG_FILE))
+    }
+
+    /// Returns the configuration file to read: the given one, or the default one if
+    /// it exists.
+    #[must_use]
+    pub fn locate(path: Option<&Path>) -> Option<PathBuf> {
+        match path {
+            Some(path) => Some(path.to_path_buf()),
+            None => Config::default_path().filter(|path| path.is_file()),
+        }
+    }
+
+    /// Reads a configuration file.
+    ///
+    /// # Errors
+    ///
+    /// This function returns an error if the file cannot be read, or an
+    /// `AppError::Config` error if it is not valid TOML or has unknown keys.
+    pub fn load(path: &Path) -> Result<Config> {
+        let contents = std::fs::read_to_string(path)
+            .wrap_err_with(|| format!("failed to read {}", path.display()))?;
+        Config::parse(&contents)
+            .wrap_err_with(|| format!("invalid configuration file {}", path.display()))
+    }
+
+    /// Parses the contents of a configuration file.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use ascii_webcam::config::Config;
+    /// let config = Config::parse("renderer = \"braille\"\n[overlays]\nstats = false").unwrap();
+    /// assert_eq!(config.renderer.as_deref(), Some("braille"));
+    /// assert!(!config.overlays.stats);
+    /// assert!(Config::parse("renderer = 3").is_err());
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// This function returns an `AppError::Config` error if the contents are not
+    /// valid TOML or have unknown keys.
+    pub fn parse(contents: &str) -> Result<Config> {
+        toml::from_str(contents).map_err(|err| {
+            let message = match err.span() {
+                Some(span) => {
+                    let line = contents[..span.start].matches('\n').count() + 1;
+                    format!("line {line}: {}", err.message())
+                }
+                None => err.message().to_string(),
+            };
+            AppError::Config(message).into()
+        })
+    }
+
+    /// Returns the command-line options completed with the settings of this file.
+    ///
+    /// Options given on the command line are kept, including the negative flags,
+    /// such as `--no-mirror`, which undo a setting of the file. A source or camera
+    /// given on the command line replaces both the source and the camera of the file.
+    ///
+    /// # Errors
+    ///
+    /// This function returns an `AppError::Config` error if a setting of the file is
+    /// invalid.
+    pub fn merge(&self, cli: &Cli) -> Result<Cli> {
+        let mut merged = cli.clone();
+        if cli.source.is_none() && cli.camera.is_none() {
+            merged.source.clone_from(&self.source);
+            merged.camera = self.camera;
+        }
+        merged.resolution = merged.resolution.or(setting(
+            "resolution",
+            self.resolution.as_deref(),
+            parse_size,
+        )?);
+        merged.capture_fps =
+            merged
+                .capture_fps
+                .or(setting("capture-fps", self.capture_fps, check_fps)?);
+        merged.fourcc = merged.fourcc.or_else(|| self.fourcc.clone());
+        merged.renderer = merged.renderer.or(named(
+            "renderer",
+            &RenderMode::ALL,
+            self.renderer.as_deref(),
+        )?);
+        // A ramp given on the command line, built-in or custom, replaces the one of the file.
+        if cli.ramp.is_none() && cli.glyphs.is_none() {
+            merged.ramp = named("ramp", &RampPreset::ALL, self.ramp.as_deref())?;
+            merged.glyphs.clone_from(&self.glyphs);
+        }
+        merged.invert = flag(cli.invert, cli.no_invert, self.invert);
+        merged.color = merged
+            .color
+            .or(setting("color", self.color.as_deref(), |name| {
+                ColorChoice::from_str(name, true)
+            })?);
+        merged.dither = merged
+            .dither
+            .or(named("dither", &Dither::ALL, self.dither.as_deref())?);
+        merged.edges = merged
+            .edges
+            .or(named("edges", &EdgeMode::ALL, self.edges.as_deref())?);
+        merged.fit = merged
+            .fit
+            .or(named("fit", &FitMode::ALL, self.fit.as_deref())?);
+        merged.mirror = flag(cli.mirror, cli.no_mirror, self.mirror);
+        merged.fps = merged.fps.or(setting("fps", self.fps, check_fps)?);
+        merged.size = merged
+            .size
+            .or(setting("size", self.size.as_deref(), parse_size)?);
+        merged.no_stats = !flag(cli.stats, cli.no_stats, Some(self.overlays.stats));
+        merged.no_status = !flag(cli.status, cli.no_status, Some(self.overlays.status));
+        Ok(merged)
+    }
+
+    /// Returns the default keymap with the keys of the `[keys]` table.
+    ///
+    /// # Errors
+    ///
+    /// This function returns an `AppError::Config` error if an action or key is unknown.
+    pub fn keymap(&self) -> Result<Keymap> {
+        Keymap::with_overrides(&self.keys)
+    }
+}
+
+/// Parses a setting of the file, if present, naming it in the error.
+fn setting<V: fmt::Display, T>(
+    key: &str,
+    value: Option<V>,
+    parse: impl FnOnce(V) -> std::result::Result<T, String>,
+) -> Result<Option<T>> {
+    value
+        .map(|value| {
+            let shown = value.to_string();
+            parse(value).map_err(|err| AppError::Config(format!("invalid {key} {shown}: {err}")))
+        })
+        .transpose()
+        .map_err(Into::into)
+}
+
+/// Returns the value of a switch: on or off if the command line says so, or the
+/// setting of the file, off by default.
+fn flag(on: bool, off: bool, value: Option<bool>) -> bool {
+    on || (!off && value.unwrap_or(false))
+}
+
+/// Parses a setting of the file among the displayed names of a cycled setting.
+fn named<T: Copy + fmt::Display>(key: &str, values: &[T], name: Option<&str>) -> Result<Option<T>> {
+    setting(key, name, |name| {
+        values
+            .iter()
+            .copied()
+            .find(|value| value.to_string() == name)
+            .ok_or_else(|| {
+                let names = values.iter().map(ToString::to_string).collect::<Vec<_>>();
+                format!("expected one of {}", names.join(", "))
+            })
+    })
+}
+
+/// Watches a configuration file and reports its changes.
+///
+/// The directory of the file is watched rather than the file itself, since many
+/// editors save by replacing the file.
+pub struct ConfigWatcher {
+    /// Stops watching when dropped.
+    _watcher: RecommendedWatcher,
+    changes: Receiver<()>,
+}
+
+impl ConfigWatcher {
+    /// Starts watching a configuration file.
+    ///
+    /// # Errors
+    ///
+    /// This function may return an error if the directory of the file cannot be watched.
+    pub fn new(path: &Path) -> Result<Self> {
+        let (sender, changes) = unbounded();
+        let file_name = path.file_name().map(ToOwned::to_owned);
+        let mut watcher =
+            notify::recommended_watcher(move |event: notify::Result<notify::Event>| {
+                let Ok(event) = event else {
+                    return;
+                };
+                let modified = !event.kind.is_access()
+                    && event
+                        .paths
+                        .iter()
+                        .any(|changed| changed.file_name() == file_name.as_deref());
+                if modified {
+                    let _ = sender.send(());
+                }
+            })
+            .wrap_err("failed to create file watcher")?;
+
+        let directory = match path.parent() {
+            Some(parent) if !parent.as_os_str().is_empty() => parent,
+            _ => Path::new("."),
+        };
+        watcher
+            .watch(directory, RecursiveMode::NonRecursive)
+            .wrap_err_with(|| format!("failed to watch {}", directory.display()))?;
+        Ok(ConfigWatcher {
+            _watcher: watcher,
+            changes,
+        })
+    }
+
+    /// Returns the channel receiving a message for each change of the file.
+    #[must_use]
+    pub fn changes(&self) -> &Receiver<()> {
+        &self.changes
+    }
+}
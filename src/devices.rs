@@ -0,0 +1,226 @@
+//! # Camera Devices
+//!
+//! This module discovers the cameras connected to the machine, so that one can
+//! be chosen with `--list-devices` or from the camera picker of the application.
+//!
+//! On Linux, cameras are read from `/sys/class/video4linux`, which names them
+//! without opening them. Elsewhere, the first camera indices are probed.
+
+use crate::error::Result;
+use color_eyre::eyre::WrapErr;
+use opencv::{
+    prelude::*,
+    videoio::{VideoCapture, CAP_ANY, CAP_PROP_FRAME_HEIGHT, CAP_PROP_FRAME_WIDTH},
+};
+use std::fmt;
+use std::path::PathBuf;
+
+/// The number of camera indices probed on systems without device nodes.
+const MAX_PROBED_INDEX: i32 = 8;
+
+/// The resolutions requested when probing what a camera supports.
+const COMMON_RESOLUTIONS: [(i32, i32); 8] = [
+    (320, 240),
+    (640, 480),
+    (800, 600),
+    (1024, 768),
+    (1280, 720),
+    (1280, 1024),
+    (1920, 1080),
+    (3840, 2160),
+];
+
+/// A camera that can be opened by index.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CameraInfo {
+    /// The index passed to `VideoCapture::new`.
+    pub index: i32,
+    /// The name reported by the driver, or a generic name.
+    pub name: String,
+    /// The device node, such as `/dev/video0`, if any.
+    pub path: Option<PathBuf>,
+}
+
+impl fmt::Display for CameraInfo {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", self.index, self.name)?;
+        if let Some(path) = &self.path {
+            write!(f, " ({})", path.display())?;
+        }
+        Ok(())
+    }
+}
+
+/// Lists the cameras connected to the machine, ordered by index.
+///
+/// Cameras are not opened, except when probing indices, which can take seconds;
+/// see `probe_resolutions` for the resolutions they support.
+///
+/// # Arguments
+///
+/// * `in_use` - The index of the camera already open, which is listed without
+///   being probed
+#[must_use]
+pub fn list_cameras(in_use: Option<i32>) -> Vec<CameraInfo> {
+    let cameras = list_video4linux("/sys/class/video4linux".as_ref());
+    if cameras.is_empty() {
+        probe_cameras(in_use)
+    } else {
+        cameras
+    }
+}
+
+/// Lists the capture devices described in a `video4linux` sysfs directory.
+///
+/// Drivers often create several nodes per camera, e.g. for metadata. Only the
+/// first node of each camera, with an `index` of `0`, captures frames.
+///
+/// # Examples
+///
+/// ```
+/// use ascii_webcam::devices::list_video4linux;
+/// assert!(list_video4linux("missing".as_ref()).is_empty());
+/// ```
+#[must_use]
+pub fn list_video4linux(class: &std::path::Path) -> Vec<CameraInfo> {
+    let Ok(entries) = std::fs::read_dir(class) else {
+        return Vec::new();
+    };
+
+    let mut cameras = entries
+        .filter_map(std::result::Result::ok)
+        .filter_map(|entry| {
+            let node = entry.file_name().to_string_lossy().into_owned();
+            let index = node.strip_prefix("video")?.parse::<i32>().ok()?;
+            let attribute = |name| {
+                std::fs::read_to_string(entry.path().join(name))
+                    .map(|value| value.trim().to_string())
+                    .ok()
+            };
+            if attribute("index").is_some_and(|value| value != "0") {
+                return None;
+            }
+            Some(CameraInfo {
+                index,
+                name: attribute("name").unwrap_or_else(|| format!("camera {index}")),
+                path: Some(PathBuf::from("/dev").join(node)),
+            })
+        })
+        .collect::<Vec<_>>();
+    cameras.sort_by_key(|camera| camera.index);
+    cameras
+}
+
+/// Lists the cameras that can be opened among the first indices.
+fn probe_cameras(in_use: Option<i32>) -> Vec<CameraInfo> {
+    (0..MAX_PROBED_INDEX)
+        .filter(|&index| {
+            Some(index) == in_use
+                || VideoCapture::new(index, CAP_ANY)
+                    .and_then(|capture| capture.is_opened())
+                    .unwrap_or(false)
+        })
+        .map(|index| CameraInfo {
+            index,
+            name: format!("camera {index}"),
+            path: None,
+        })
+        .collect()
+}
+
+/// Returns the distinct resolutions a camera settles on when asked for common ones.
+///
+/// Drivers pick the closest supported resolution, so this lists what is actually
+/// available rather than what was requested. Probing opens the camera, so it
+/// should not be in use.
+///
+/// # Errors
+///
+/// This function may return an error if the camera cannot be opened or configured.
+pub fn probe_resolutions(index: i32) -> Result<Vec<(i32, i32)>> {
+    let mut capture =
+        VideoCapture::new(index, CAP_ANY).wrap_err("failed to create VideoCapture")?;
+    let mut resolutions = Vec::new();
+    if !capture.is_opened()? {
+        return Ok(resolutions);
+    }
+
+    for (width, height) in COMMON_RESOLUTIONS {
+        capture
+            .set(CAP_PROP_FRAME_WIDTH, f64::from(width))
+            .wrap_err("failed to set width")?;
+        capture
+            .set(CAP_PROP_FRAME_HEIGHT, f64::from(height))
+            .wrap_err("failed to set height")?;
+        // Resolutions are reported as whole numbers.
+        #[allow(clippy::cast_possible_truncation)]
+        let actual = (
+            capture.get(CAP_PROP_FRAME_WIDTH)?.round() as i32,
+            capture.get(CAP_PROP_FRAME_HEIGHT)?.round() as i32,
+        );
+        if actual.0 > 0 && actual.1 > 0 && !resolutions.contains(&actual) {
+            resolutions.push(actual);
+        }
+    }
+    resolutions.sort_unstable();
+    Ok(resolutions)
+}
+
+/// The state of the camera picker popup.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DevicePicker {
+    /// The cameras to choose from.
+    pub cameras: Vec<CameraInfo>,
+    /// The index of the highlighted camera in `cameras`.
+    pub selected: usize,
+    /// Why the last chosen camera could not be opened, if it could not.
+    pub error: Option<String>,
+    /// Whether the cameras are still being listed.
+    pub searching: bool,
+}
+
+impl DevicePicker {
+    /// Creates a picker highlighting the camera with the given index, if listed.
+    #[must_use]
+    pub fn new(cameras: Vec<CameraInfo>, current: Option<i32>) -> Self {
+        let selected = cameras
+            .iter()
+            .position(|camera| Some(camera.index) == current)
+            .unwrap_or(0);
+        DevicePicker {
+            cameras,
+            selected,
+            error: None,
+            searching: false,
+        }
+    }
+
+    /// Creates an empty picker shown while the cameras are listed.
+    #[must_use]
+    pub fn searching() -> Self {
+        DevicePicker {
+            searching: true,
+            ..DevicePicker::new(Vec::new(), None)
+        }
+    }
+
+    /// Highlights the next camera, wrapping around at the end.
+    pub fn next(&mut self) {
+        if !self.cameras.is_empty() {
+            self.selected = (self.selected + 1) % self.cameras.len();
+        }
+    }
+
+    /// Highlights the previous camera, wrapping around at the start.
+    pub fn previous(&mut self) {
+        if !self.cameras.is_empty() {
+            self.selected = (self.selected + self.cameras.len() - 1) % self.cameras.len();
+        }
+    }
+
+    /// Returns the highlighted camera, if any.
+    #[must_use]
+    pub fn selected(&self) -> Option<&CameraInfo> {
+        self.cameras.get(self.selected)
+    }
+}
@@ -0,0 +1,340 @@
+//! # Dithering
+//!
+//! This module quantizes grayscale pixels to a small number of levels, e.g.
+//! the glyphs of a character ramp or the two states of a Braille dot, while
+//! hiding the banding that per-pixel quantization causes on smooth gradients.
+//!
+//! Two families of methods are provided:
+//! - error diffusion (Floyd–Steinberg, Atkinson, Jarvis–Judice–Ninke), which
+//!   spreads the quantization error of each pixel to its unvisited neighbours
+//! - ordered dithering (Bayer 2x2/4x4/8x8 and blue noise), which offsets each
+//!   pixel by a position-dependent threshold
+
+use lazy_static::lazy_static;
+use std::fmt;
+
+/// The side length of the generated blue-noise threshold map.
+const BLUE_NOISE_SIZE: usize = 32;
+
+/// The Floyd–Steinberg kernel as `(dx, dy, weight)`, with weights in sixteenths.
+const FLOYD_STEINBERG: [(isize, usize, f32); 4] = [
+    (1, 0, 7.0 / 16.0),
+    (-1, 1, 3.0 / 16.0),
+    (0, 1, 5.0 / 16.0),
+    (1, 1, 1.0 / 16.0),
+];
+
+/// The Atkinson kernel, which only diffuses three quarters of the error.
+const ATKINSON: [(isize, usize, f32); 6] = [
+    (1, 0, 1.0 / 8.0),
+    (2, 0, 1.0 / 8.0),
+    (-1, 1, 1.0 / 8.0),
+    (0, 1, 1.0 / 8.0),
+    (1, 1, 1.0 / 8.0),
+    (0, 2, 1.0 / 8.0),
+];
+
+/// The Jarvis–Judice–Ninke kernel, with weights in 48ths.
+const JARVIS_JUDICE_NINKE: [(isize, usize, f32); 12] = [
+    (1, 0, 7.0 / 48.0),
+    (2, 0, 5.0 / 48.0),
+    (-2, 1, 3.0 / 48.0),
+    (-1, 1, 5.0 / 48.0),
+    (0, 1, 7.0 / 48.0),
+    (1, 1, 5.0 / 48.0),
+    (2, 1, 3.0 / 48.0),
+    (-2, 2, 1.0 / 48.0),
+    (-1, 2, 3.0 / 48.0),
+    (0, 2, 5.0 / 48.0),
+    (1, 2, 3.0 / 48.0),
+    (2, 2, 1.0 / 48.0),
+];
+
+lazy_static! {
+    static ref BLUE_NOISE: Vec<f32> = void_and_cluster(BLUE_NOISE_SIZE, 1.5);
+}
+
+/// The available dithering methods.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Dither {
+    /// Each pixel is quantized independently.
+    #[default]
+    None,
+    /// Floyd–Steinberg error diffusion.
+    FloydSteinberg,
+    /// Atkinson error diffusion, which keeps more contrast.
+    Atkinson,
+    /// Jarvis–Judice–Ninke error diffusion, which spreads the error further.
+    JarvisJudiceNinke,
+    /// Ordered dithering with a 2x2 Bayer matrix.
+    Bayer2,
+    /// Ordered dithering with a 4x4 Bayer matrix.
+    Bayer4,
+    /// Ordered dithering with an 8x8 Bayer matrix.
+    Bayer8,
+    /// Ordered dithering with a blue-noise threshold map.
+    BlueNoise,
+}
+
+impl Dither {
+    /// All dithering methods, in the order they are cycled through.
+    pub const ALL: [Dither; 8] = [
+        Dither::None,
+        Dither::FloydSteinberg,
+        Dither::Atkinson,
+        Dither::JarvisJudiceNinke,
+        Dither::Bayer2,
+        Dither::Bayer4,
+        Dither::Bayer8,
+        Dither::BlueNoise,
+    ];
+
+    /// Returns the dithering method following this one, wrapping around at the end.
+    #[must_use]
+    pub fn next(self) -> Dither {
+        let index = Dither::ALL
+            .iter()
+            .position(|&dither| dither == self)
+            .unwrap_or(0);
+        Dither::ALL[(index + 1) % Dither::ALL.len()]
+    }
+}
+
+impl fmt::Display for Dither {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Dither::None => "none",
+            Dither::FloydSteinberg => "floyd-steinberg",
+            Dither::Atkinson => "atkinson",
+            Dither::JarvisJudiceNinke => "jarvis-judice-ninke",
+            Dither::Bayer2 => "bayer2",
+            Dither::Bayer4 => "bayer4",
+            Dither::Bayer8 => "bayer8",
+            Dither::BlueNoise => "blue-noise",
+        };
+        f.write_str(name)
+    }
+}
+
+/// Returns the Bayer matrix entry at `x`, `y` for a matrix of `2^order` x `2^order`.
+///
+/// # Examples
+///
+/// ```
+/// use ascii_webcam::dither::bayer_index;
+/// // The 2x2 Bayer matrix is [[0, 2], [3, 1]].
+/// assert_eq!(bayer_index(1, 1, 0), 2);
+/// assert_eq!(bayer_index(1, 0, 1), 3);
+/// // The 4x4 matrix starts with [0, 8, 2, 10].
+/// assert_eq!(bayer_index(2, 1, 0), 8);
+/// ```
+#[must_use]
+pub fn bayer_index(order: u32, x: usize, y: usize) -> usize {
+    (0..order).fold(0, |index, bit| {
+        let xb = (x >> bit) & 1;
+        let yb = (y >> bit) & 1;
+        (index << 2) | ((xb ^ yb) << 1) | yb
+    })
+}
+
+/// Quantizes grayscale pixels to `levels` evenly spaced levels.
+///
+/// # Arguments
+///
+/// * `pixels` - The grayscale pixels, row by row
+/// * `width` - The width of the image in pixels
+/// * `levels` - The number of output levels, at least 2
+/// * `method` - The dithering method
+///
+/// # Returns
+///
+/// Returns the level (`0` for black up to `levels - 1` for white) of each pixel.
+///
+/// # Examples
+///
+/// ```
+/// use ascii_webcam::dither::{dither, Dither};
+/// let levels = dither(&[0, 100, 200, 255], 4, 2, Dither::None);
+/// assert_eq!(levels, vec![0, 0, 1, 1]);
+/// ```
+#[must_use]
+pub fn dither(pixels: &[u8], width: usize, levels: usize, method: Dither) -> Vec<usize> {
+    let max_level = levels.max(2) - 1;
+    // The step between two levels, as a luminance difference.
+    #[allow(clippy::cast_precision_loss)]
+    let step = 255.0 / max_level as f32;
+
+    let quantize = |value: f32| -> usize {
+        let level = (value / step).round().clamp(0.0, 255.0);
+        // The level is clamped to a small non-negative range above.
+        #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+        let level = level as usize;
+        level.min(max_level)
+    };
+
+    let ordered = |threshold: &dyn Fn(usize, usize) -> f32| -> Vec<usize> {
+        pixels
+            .iter()
+            .enumerate()
+            .map(|(i, &value)| {
+                let offset = (threshold(i % width.max(1), i / width.max(1)) - 0.5) * step;
+                quantize(f32::from(value) + offset)
+            })
+            .collect()
+    };
+
+    match method {
+        Dither::None => pixels
+            .iter()
+            .map(|&value| quantize(f32::from(value)))
+            .collect(),
+        Dither::FloydSteinberg => diffuse(pixels, width, step, &FLOYD_STEINBERG, quantize),
+        Dither::Atkinson => diffuse(pixels, width, step, &ATKINSON, quantize),
+        Dither::JarvisJudiceNinke => diffuse(pixels, width, step, &JARVIS_JUDICE_NINKE, quantize),
+        Dither::Bayer2 => ordered(&|x, y| bayer_threshold(1, x, y)),
+        Dither::Bayer4 => ordered(&|x, y| bayer_threshold(2, x, y)),
+        Dither::Bayer8 => ordered(&|x, y| bayer_threshold(3, x, y)),
+        Dither::BlueNoise => ordered(&|x, y| {
+            BLUE_NOISE[(y % BLUE_NOISE_SIZE) * BLUE_NOISE_SIZE + x % BLUE_NOISE_SIZE]
+        }),
+    }
+}
+
+/// Returns the Bayer threshold in `[0, 1)` at `x`, `y` for a `2^order` matrix.
+fn bayer_threshold(order: u32, x: usize, y: usize) -> f32 {
+    let cells = 1usize << (2 * order);
+    // The matrices have at most 64 entries, which f32 represents exactly.
+    #[allow(clippy::cast_precision_loss)]
+    let threshold = (bayer_index(order, x, y) as f32 + 0.5) / cells as f32;
+    threshold
+}
+
+/// Quantizes pixels with error diffusion using the given kernel.
+fn diffuse(
+    pixels: &[u8],
+    width: usize,
+    step: f32,
+    kernel: &[(isize, usize, f32)],
+    quantize: impl Fn(f32) -> usize,
+) -> Vec<usize> {
+    let width = width.max(1);
+    let height = pixels.len() / width;
+    let mut values: Vec<f32> = pixels.iter().map(|&value| f32::from(value)).collect();
+    let mut levels = vec![0; pixels.len()];
+
+    for y in 0..height {
+        for x in 0..width {
+            let i = y * width + x;
+            let level = quantize(values[i]);
+            levels[i] = level;
+
+            #[allow(clippy::cast_precision_loss)]
+            let error = values[i] - level as f32 * step;
+            for &(dx, dy, weight) in kernel {
+                let Some(nx) = x.checked_add_signed(dx).filter(|&nx| nx < width) else {
+                    continue;
+                };
+                let ny = y + dy;
+                if ny < height {
+                    values[ny * width + nx] += error * weight;
+                }
+            }
+        }
+    }
+
+    levels
+}
+
+/// Generates a blue-noise threshold map with the void-and-cluster algorithm.
+///
+/// Returns `size * size` thresholds in `[0, 1)`, row by row, that tile seamlessly.
+fn void_and_cluster(size: usize, sigma: f32) -> Vec<f32> {
+    let count = size * size;
+
+    // The gaussian weight between two pixels, with wrap-around distances.
+    let weights: Vec<f32> = (0..count)
+        .map(|i| {
+            let (dx, dy) = (i % size, i / size);
+            let dx = dx.min(size - dx);
+            let dy = dy.min(size - dy);
+            #[allow(clippy::cast_precision_loss)]
+            let distance = (dx * dx + dy * dy) as f32;
+            (-distance / (2.0 * sigma * sigma)).exp()
+        })
+        .collect();
+    let weight = |a: usize, b: usize| {
+        let dx = (a % size + size - b % size) % size;
+        let dy = (a / size + size - b / size) % size;
+        weights[dy * size + dx]
+    };
+
+    let toggle = |pattern: &mut [bool], energy: &mut [f32], i: usize| {
+        pattern[i] = !pattern[i];
+        let sign = if pattern[i] { 1.0 } else { -1.0 };
+        for (j, e) in energy.iter_mut().enumerate() {
+            *e += sign * weight(i, j);
+        }
+    };
+    // The set pixel with the highest energy, or the unset one with the lowest.
+    let tightest_cluster = |pattern: &[bool], energy: &[f32]| {
+        (0..count)
+            .filter(|&i| pattern[i])
+            .max_by(|&a, &b| energy[a].total_cmp(&energy[b]))
+            .unwrap_or(0)
+    };
+    let largest_void = |pattern: &[bool], energy: &[f32]| {
+        (0..count)
+            .filter(|&i| !pattern[i])
+            .min_by(|&a, &b| energy[a].total_cmp(&energy[b]))
+            .unwrap_or(0)
+    };
+
+    // Start from a deterministic pseudo-random pattern with about 10% of pixels set.
+    let mut pattern = vec![false; count];
+    let mut energy = vec![0.0; count];
+    let mut seed: u32 = 0x9E37_79B9;
+    let initial = count / 10;
+    while pattern.iter().filter(|&&set| set).count() < initial {
+        seed ^= seed << 13;
+        seed ^= seed >> 17;
+        seed ^= seed << 5;
+        let i = usize::try_from(seed).unwrap_or_default() % count;
+        if !pattern[i] {
+            toggle(&mut pattern, &mut energy, i);
+        }
+    }
+
+    // Move pixels from the tightest cluster to the largest void until stable.
+    for _ in 0..count {
+        let cluster = tightest_cluster(&pattern, &energy);
+        toggle(&mut pattern, &mut energy, cluster);
+        let void = largest_void(&pattern, &energy);
+        toggle(&mut pattern, &mut energy, void);
+        if void == cluster {
+            break;
+        }
+    }
+
+    let mut ranks = vec![0; count];
+
+    // Rank the initial pixels by removing the tightest cluster first.
+    let (mut removing, mut removing_energy) = (pattern.clone(), energy.clone());
+    for rank in (0..initial).rev() {
+        let cluster = tightest_cluster(&removing, &removing_energy);
+        toggle(&mut removing, &mut removing_energy, cluster);
+        ranks[cluster] = rank;
+    }
+
+    // Rank the remaining pixels by filling the largest void first.
+    for rank in initial..count {
+        let void = largest_void(&pattern, &energy);
+        toggle(&mut pattern, &mut energy, void);
+        ranks[void] = rank;
+    }
+
+    #[allow(clippy::cast_precision_loss)]
+    ranks
+        .into_iter()
+        .map(|rank| (rank as f32 + 0.5) / count as f32)
+        .collect()
+}
@@ -0,0 +1,182 @@
+//! # Edge Detection
+//!
+//! This module finds edges in a grayscale frame and picks a glyph that
+//! follows the direction of each edge (`| / - \ _`), so that outlines stay
+//! crisp instead of being drawn with the luminance ramp.
+
+use crate::error::Result;
+use color_eyre::eyre::WrapErr;
+use opencv::{
+    core::{self, Mat},
+    imgproc,
+    prelude::*,
+};
+use std::fmt;
+
+/// The available edge detectors.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum EdgeMode {
+    /// No edge detection.
+    #[default]
+    Off,
+    /// Edges where the Sobel gradient magnitude exceeds the threshold.
+    Sobel,
+    /// Thin edges found by the Canny detector, using the threshold as its lower bound.
+    Canny,
+}
+
+impl EdgeMode {
+    /// All edge modes, in the order they are cycled through.
+    pub const ALL: [EdgeMode; 3] = [EdgeMode::Off, EdgeMode::Sobel, EdgeMode::Canny];
+
+    /// Returns the edge mode following this one, wrapping around at the end.
+    #[must_use]
+    pub fn next(self) -> EdgeMode {
+        let index = EdgeMode::ALL
+            .iter()
+            .position(|&mode| mode == self)
+            .unwrap_or(0);
+        EdgeMode::ALL[(index + 1) % EdgeMode::ALL.len()]
+    }
+}
+
+impl fmt::Display for EdgeMode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            EdgeMode::Off => "off",
+            EdgeMode::Sobel => "sobel",
+            EdgeMode::Canny => "canny",
+        };
+        f.write_str(name)
+    }
+}
+
+/// Returns the glyph following an edge with the given image gradient.
+///
+/// The gradient points towards the brighter side, with `y` growing downwards.
+/// Horizontal edges use `_` when the brighter side is below, since the edge
+/// then lies at the bottom of the cell, and `-` otherwise.
+///
+/// # Examples
+///
+/// ```
+/// use ascii_webcam::edges::edge_glyph;
+/// assert_eq!(edge_glyph(1.0, 0.0), '|');
+/// assert_eq!(edge_glyph(1.0, 1.0), '/');
+/// assert_eq!(edge_glyph(1.0, -1.0), '\\');
+/// assert_eq!(edge_glyph(0.0, 1.0), '_');
+/// assert_eq!(edge_glyph(0.0, -1.0), '-');
+/// ```
+#[must_use]
+pub fn edge_glyph(gx: f32, gy: f32) -> char {
+    // The gradient direction modulo 180 degrees, since an edge has no orientation.
+    let mut angle = gy.atan2(gx).to_degrees();
+    if angle < 0.0 {
+        angle += 180.0;
+    }
+
+    if !(22.5..157.5).contains(&angle) {
+        '|'
+    } else if angle < 67.5 {
+        '/'
+    } else if angle < 112.5 {
+        if gy > 0.0 {
+            '_'
+        } else {
+            '-'
+        }
+    } else {
+        '\\'
+    }
+}
+
+/// Finds the edges of a grayscale image.
+///
+/// # Arguments
+///
+/// * `gray` - The 8-bit grayscale image, usually already resized to one pixel per cell
+/// * `mode` - The edge detector to use
+/// * `threshold` - The luminance difference across an edge needed to detect it
+///
+/// # Returns
+///
+/// Returns, for each pixel row by row, the direction glyph of the edge at that pixel,
+/// or `None` if there is no edge. With `EdgeMode::Off`, no edges are returned.
+///
+/// # Errors
+///
+/// This function may return an error if:
+/// - Computing the image gradients fails
+/// - Running the Canny detector fails
+/// - Accessing the gradient or edge values fails
+pub fn detect_edges(gray: &Mat, mode: EdgeMode, threshold: u8) -> Result<Vec<Option<char>>> {
+    if mode == EdgeMode::Off {
+        return Ok(vec![None; gray.total()]);
+    }
+
+    let mut gx = Mat::default();
+    imgproc::sobel(
+        gray,
+        &mut gx,
+        core::CV_32F,
+        1,
+        0,
+        3,
+        1.0,
+        0.0,
+        core::BORDER_DEFAULT,
+    )
+    .wrap_err("failed to compute horizontal gradient")?;
+    let mut gy = Mat::default();
+    imgproc::sobel(
+        gray,
+        &mut gy,
+        core::CV_32F,
+        0,
+        1,
+        3,
+        1.0,
+        0.0,
+        core::BORDER_DEFAULT,
+    )
+    .wrap_err("failed to compute vertical gradient")?;
+    let gx = gx
+        .data_typed::<f32>()
+        .wrap_err("failed to access horizontal gradient")?;
+    let gy = gy
+        .data_typed::<f32>()
+        .wrap_err("failed to access vertical gradient")?;
+
+    // A 3x3 Sobel kernel responds to a step of `d` with a gradient of `4 * d`.
+    let threshold = f32::from(threshold) * 4.0;
+
+    let is_edge: Vec<bool> = if mode == EdgeMode::Canny {
+        let mut edges = Mat::default();
+        imgproc::canny(
+            gray,
+            &mut edges,
+            f64::from(threshold),
+            f64::from(threshold) * 2.0,
+            3,
+            false,
+        )
+        .wrap_err("failed to run Canny edge detection")?;
+        edges
+            .data_bytes()
+            .wrap_err("failed to access edges")?
+            .iter()
+            .map(|&value| value > 0)
+            .collect()
+    } else {
+        gx.iter()
+            .zip(gy)
+            .map(|(x, y)| x.hypot(*y) > threshold)
+            .collect()
+    };
+
+    Ok(is_edge
+        .into_iter()
+        .zip(gx.iter().zip(gy))
+        .map(|(edge, (x, y))| edge.then(|| edge_glyph(*x, *y)))
+        .collect())
+}
@@ -1,9 +1,15 @@
 //! # Error Handling
 //!
 //! This module defines custom error types and a Result type alias
-//! for use throughout the ASCII Webcam application.
+//! for use throughout the ASCII Webcam application, and installs the hooks
+//! reporting them.
 
-use color_eyre::Report;
+use color_eyre::config::HookBuilder;
+use color_eyre::eyre::{self, EyreHandler};
+use color_eyre::{Handler, Report};
+use std::backtrace::Backtrace;
+use std::error::Error as StdError;
+use std::fmt;
 use thiserror::Error;
 
 /// A type alias for Results that use custom `AppError` type.
@@ -29,8 +35,72 @@ pub enum AppError {
     #[error("Camera error: {0}")]
     Camera(String),
 
+    /// Represents frame sources, such as files, that cannot be opened or read.
+    #[error("Source error: {0}")]
+    Source(String),
+
+    /// Represents invalid user-supplied settings.
+    #[error("Configuration error: {0}")]
+    Config(String),
+
     /// Represents unknown errors
     #[allow(dead_code)]
     #[error("Unexpected error occurred: {0}")]
     Other(String),
 }
+
+/// Installs `color_eyre` as the reporter of errors and panics.
+///
+/// # Arguments
+///
+/// * `backtraces` - Whether errors are reported with a backtrace of where they
+///   were created, whatever `RUST_BACKTRACE` says
+///
+/// # Errors
+///
+/// This function returns an error if the hooks were already installed.
+pub fn install_hooks(backtraces: bool) -> Result<()> {
+    // The hint to set `RUST_BACKTRACE` is pointless once backtraces are shown
+    let (panic_hook, eyre_hook) = HookBuilder::default()
+        .display_env_section(!backtraces)
+        .try_into_hooks()?;
+    let eyre_hook = eyre_hook.into_eyre_hook();
+    eyre::set_hook(Box::new(move |error| {
+        let handler = eyre_hook(error);
+        // Reports already carry a backtrace when the environment asks for one
+        let captured = handler
+            .downcast_ref::<Handler>()
+            .is_some_and(|handler| handler.backtrace().is_some());
+        if backtraces && !captured {
+            Box::new(BacktraceHandler {
+                inner: handler,
+                backtrace: Backtrace::force_capture(),
+            })
+        } else {
+            handler
+        }
+    }))?;
+    panic_hook.install();
+    Ok(())
+}
+
+/// Reports errors like another handler, followed by a backtrace.
+struct BacktraceHandler {
+    inner: Box<dyn EyreHandler>,
+    backtrace: Backtrace,
+}
+
+impl EyreHandler for BacktraceHandler {
+    fn debug(&self, error: &(dyn StdError + 'static), f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.inner.debug(error, f)?;
+        write!(f, "\n\nBacktrace:\n{}", self.backtrace)
+    }
+
+    fn display(&self, error: &(dyn StdError + 'static), f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.inner.display(error, f)
+    }
+
+    fn track_caller(&mut self, location: &'static std::panic::Location<'static>) {
+        self.inner.track_caller(location);
+    }
+}
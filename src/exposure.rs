@@ -0,0 +1,220 @@
+//! # Auto-Exposure
+//!
+//! This module measures the black and white points of each frame from its
+//! luminance histogram and smooths them over time, so that the image can be
+//! stretched to the full character ramp without its brightness pumping
+//! between frames.
+
+use crate::error::Result;
+use color_eyre::eyre::WrapErr;
+use opencv::{core::Mat, prelude::*};
+use std::fmt;
+
+/// The black and white points of an image.
+///
+/// Luminance values at or below the black point map to black, and values at
+/// or above the white point map to white.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Levels {
+    pub black: u8,
+    pub white: u8,
+}
+
+impl Levels {
+    /// Creates new levels, keeping the white point above the black point.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use ascii_webcam::exposure::Levels;
+    /// let levels = Levels::new(200, 100);
+    /// assert_eq!((levels.black, levels.white), (200, 201));
+    /// ```
+    #[must_use]
+    pub fn new(black: u8, white: u8) -> Self {
+        let black = black.min(254);
+        Levels {
+            black,
+            white: white.max(black + 1),
+        }
+    }
+
+    /// Returns `true` if the levels cover the full range and leave the image unchanged.
+    #[must_use]
+    pub fn is_full_range(self) -> bool {
+        self == Levels::default()
+    }
+
+    /// Stretches a luminance value from these levels to the full range.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use ascii_webcam::exposure::Levels;
+    /// let levels = Levels::new(50, 150);
+    /// assert_eq!(levels.stretch(40.0), 0.0);
+    /// assert_eq!(levels.stretch(100.0), 127.5);
+    /// assert_eq!(levels.stretch(150.0), 255.0);
+    /// ```
+    #[must_use]
+    pub fn stretch(self, value: f64) -> f64 {
+        let black = f64::from(self.black);
+        let white = f64::from(self.white);
+        ((value - black) * 255.0 / (white - black)).clamp(0.0, 255.0)
+    }
+}
+
+impl Default for Levels {
+    fn default() -> Self {
+        Levels {
+            black: 0,
+            white: 255,
+        }
+    }
+}
+
+impl fmt::Display for Levels {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}-{}", self.black, self.white)
+    }
+}
+
+/// Measures the levels of an 8-bit grayscale image from its histogram.
+///
+/// # Arguments
+///
+/// * `gray` - The grayscale image to measure
+/// * `low` - The fraction of pixels, from `0.0` to `1.0`, allowed to be darker than the black point
+/// * `high` - The fraction of pixels allowed to be brighter than the white point
+///
+/// # Returns
+///
+/// Returns the measured levels, or the full range for an empty image.
+///
+/// # Errors
+///
+/// This function returns an error if the pixels of `gray` cannot be accessed.
+pub fn measure(gray: &Mat, low: f64, high: f64) -> Result<Levels> {
+    let pixels = gray.data_bytes().wrap_err("failed to access pixels")?;
+    if pixels.is_empty() {
+        return Ok(Levels::default());
+    }
+
+    let mut histogram = [0usize; 256];
+    for &value in pixels {
+        histogram[usize::from(value)] += 1;
+    }
+
+    // Pixel counts stay far below 2^52, so they are exact as `f64`.
+    #[allow(clippy::cast_precision_loss)]
+    let total = pixels.len() as f64;
+    // Returns the first value, in the given order, past which more than `fraction` of the pixels lie.
+    let percentile = |values: &mut dyn Iterator<Item = u8>, fraction: f64| {
+        let mut seen = 0;
+        for value in values {
+            seen += histogram[usize::from(value)];
+            #[allow(clippy::cast_precision_loss)]
+            let seen_fraction = seen as f64 / total;
+            if seen_fraction > fraction {
+                return Some(value);
+            }
+        }
+        None
+    };
+
+    let black = percentile(&mut (0..=255), low).unwrap_or(0);
+    let white = percentile(&mut (0..=255).rev(), high).unwrap_or(u8::MAX);
+    Ok(Levels::new(black, white))
+}
+
+/// Measures the levels of each frame and smooths them over time.
+#[derive(Debug, Clone, PartialEq)]
+pub struct AutoExposure {
+    /// Whether the levels are measured and applied.
+    pub enabled: bool,
+    /// The fraction of pixels clipped to black and to white.
+    pub clip: f64,
+    /// How much of each new measurement is blended into the current levels, from
+    /// `0.0` (never change) to `1.0` (no smoothing).
+    pub smoothing: f64,
+    /// The smallest distance kept between the black and white points, so that
+    /// flat images are not stretched into noise.
+    pub min_range: u8,
+    current: Option<(f64, f64)>,
+}
+
+impl AutoExposure {
+    /// Returns the current levels, or the full range if nothing was measured yet.
+    #[must_use]
+    pub fn levels(&self) -> Levels {
+        let Some((black, white)) = self.current else {
+            return Levels::default();
+        };
+        // Both points are blended from values within the range of `u8`.
+        #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+        let (black, white) = (black.round() as u8, white.round() as u8);
+        Levels::new(black, white)
+    }
+
+    /// Toggles auto-exposure, forgetting the levels measured so far.
+    pub fn toggle(&mut self) {
+        self.enabled = !self.enabled;
+        self.current = None;
+    }
+
+    /// Measures the levels of an 8-bit grayscale frame and blends them into the
+    /// current levels.
+    ///
+    /// The frame should be equalized already, since the levels are applied after
+    /// equalization.
+    ///
+    /// # Returns
+    ///
+    /// Returns the smoothed levels to apply to the frame, or the full range if
+    /// auto-exposure is disabled.
+    ///
+    /// # Errors
+    ///
+    /// This function returns an error if the pixel values cannot be accessed.
+    pub fn update(&mut self, gray: &Mat) -> Result<Levels> {
+        if !self.enabled {
+            return Ok(Levels::default());
+        }
+
+        let measured = measure(gray, self.clip, self.clip)?;
+
+        // Widen narrow ranges evenly around their center.
+        let mut black = f64::from(measured.black);
+        let mut white = f64::from(measured.white);
+        let missing = f64::from(self.min_range) - (white - black);
+        if missing > 0.0 {
+            black -= missing / 2.0;
+            white += missing / 2.0;
+            let shift = (-black).max(0.0) - (white - 255.0).max(0.0);
+            black += shift;
+            white += shift;
+        }
+
+        let blended = match self.current {
+            Some((current_black, current_white)) => (
+                current_black + (black - current_black) * self.smoothing,
+                current_white + (white - current_white) * self.smoothing,
+            ),
+            None => (black, white),
+        };
+        self.current = Some(blended);
+        Ok(self.levels())
+    }
+}
+
+impl Default for AutoExposure {
+    fn default() -> Self {
+        AutoExposure {
+            enabled: false,
+            clip: 0.01,
+            smoothing: 0.1,
+            min_range: 32,
+            current: None,
+        }
+    }
+}
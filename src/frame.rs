@@ -0,0 +1,307 @@
+//! # ASCII Frames
+//!
+//! This module defines `AsciiFrame`, the grid of character cells produced by the
+//! ASCII conversion, along with helpers to iterate over it and convert it to
+//! plain text, text with ANSI escape sequences, or styled `ratatui` text.
+
+use crate::color::{ColorMode, Rgb};
+use ratatui::{
+    style::Style,
+    text::{Line, Span, Text},
+};
+use std::fmt;
+
+/// A single character cell of an ASCII frame.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Cell {
+    /// The character displayed in the cell.
+    pub glyph: char,
+    /// The foreground color of the cell, if any.
+    pub fg: Option<Rgb>,
+    /// The background color of the cell, if any.
+    pub bg: Option<Rgb>,
+    /// The luminance (0-255) of the source pixels the cell represents.
+    pub luminance: u8,
+}
+
+impl Cell {
+    /// Creates a new uncolored cell.
+    #[must_use]
+    pub const fn new(glyph: char, luminance: u8) -> Self {
+        Cell {
+            glyph,
+            fg: None,
+            bg: None,
+            luminance,
+        }
+    }
+
+    /// Returns the cell with the given foreground color.
+    #[must_use]
+    pub const fn with_fg(mut self, fg: Rgb) -> Self {
+        self.fg = Some(fg);
+        self
+    }
+
+    /// Returns the cell with the given background color.
+    #[must_use]
+    pub const fn with_bg(mut self, bg: Rgb) -> Self {
+        self.bg = Some(bg);
+        self
+    }
+
+    /// Returns the ANSI SGR parameters of the cell colors for the given color mode.
+    fn sgr(&self, color_mode: ColorMode) -> Vec<String> {
+        let fg = self.fg.and_then(|fg| color_mode.sgr(fg, false));
+        let bg = self.bg.and_then(|bg| color_mode.sgr(bg, true));
+        fg.into_iter().chain(bg).collect()
+    }
+
+    /// Returns the `ratatui` style of the cell for the given color mode.
+    fn style(&self, color_mode: ColorMode) -> Style {
+        let mut style = Style::default();
+        if let Some(fg) = self.fg.and_then(|fg| color_mode.to_color(fg)) {
+            style = style.fg(fg);
+        }
+        if let Some(bg) = self.bg.and_then(|bg| color_mode.to_color(bg)) {
+            style = style.bg(bg);
+        }
+        style
+    }
+}
+
+impl Default for Cell {
+    fn default() -> Self {
+        Cell::new(' ', 0)
+    }
+}
+
+/// A grid of character cells representing one converted video frame.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct AsciiFrame {
+    width: usize,
+    height: usize,
+    cells: Vec<Cell>,
+}
+
+impl AsciiFrame {
+    /// Creates a new frame of the given size filled with blank cells.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use ascii_webcam::frame::AsciiFrame;
+    /// let frame = AsciiFrame::new(3, 2);
+    /// assert_eq!(frame.to_string(), "   \n   ");
+    /// ```
+    #[must_use]
+    pub fn new(width: usize, height: usize) -> Self {
+        AsciiFrame {
+            width,
+            height,
+            cells: vec![Cell::default(); width * height],
+        }
+    }
+
+    /// Creates a new frame from cells laid out row by row.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the number of cells is not `width * height`.
+    #[must_use]
+    pub fn from_cells(width: usize, height: usize, cells: Vec<Cell>) -> Self {
+        assert_eq!(
+            cells.len(),
+            width * height,
+            "cell count does not match frame size"
+        );
+        AsciiFrame {
+            width,
+            height,
+            cells,
+        }
+    }
+
+    /// Returns the frame centered in a larger frame of blank cells.
+    ///
+    /// Frames that are already at least as large in a direction are not padded in it.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use ascii_webcam::frame::{AsciiFrame, Cell};
+    /// let frame = AsciiFrame::from_cells(1, 1, vec![Cell::new('@', 255)]);
+    /// assert_eq!(frame.letterbox(3, 3).to_string(), "   \n @ \n   ");
+    /// ```
+    #[must_use]
+    pub fn letterbox(&self, width: usize, height: usize) -> AsciiFrame {
+        let width = width.max(self.width);
+        let height = height.max(self.height);
+        let left = (width - self.width) / 2;
+        let top = (height - self.height) / 2;
+
+        let mut padded = AsciiFrame::new(width, height);
+        for (x, y, cell) in self.indexed_cells() {
+            if let Some(target) = padded.get_mut(left + x, top + y) {
+                *target = *cell;
+            }
+        }
+        padded
+    }
+
+    /// Returns the width of the frame in cells.
+    #[must_use]
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    /// Returns the height of the frame in cells.
+    #[must_use]
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    /// Returns `true` if the frame contains no cells.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.cells.is_empty()
+    }
+
+    /// Returns the cell at the given column and row, if it exists.
+    #[must_use]
+    pub fn get(&self, x: usize, y: usize) -> Option<&Cell> {
+        if x < self.width && y < self.height {
+            self.cells.get(y * self.width + x)
+        } else {
+            None
+        }
+    }
+
+    /// Returns a mutable reference to the cell at the given column and row, if it exists.
+    pub fn get_mut(&mut self, x: usize, y: usize) -> Option<&mut Cell> {
+        if x < self.width && y < self.height {
+            self.cells.get_mut(y * self.width + x)
+        } else {
+            None
+        }
+    }
+
+    /// Returns an iterator over the rows of the frame.
+    pub fn rows(&self) -> impl Iterator<Item = &[Cell]> {
+        // `chunks_exact` panics on a zero chunk size, which happens for empty frames.
+        self.cells.chunks_exact(self.width.max(1))
+    }
+
+    /// Returns an iterator over all cells of the frame, row by row.
+    pub fn cells(&self) -> impl Iterator<Item = &Cell> {
+        self.cells.iter()
+    }
+
+    /// Returns an iterator over all cells of the frame along with their column and row.
+    pub fn indexed_cells(&self) -> impl Iterator<Item = (usize, usize, &Cell)> {
+        let width = self.width.max(1);
+        self.cells
+            .iter()
+            .enumerate()
+            .map(move |(i, cell)| (i % width, i / width, cell))
+    }
+
+    /// Converts the frame to styled `ratatui` text.
+    ///
+    /// Consecutive cells that map to the same terminal style are merged into a single
+    /// `Span`, which keeps the number of spans low in the 16 and 256 color modes.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use ascii_webcam::color::ColorMode;
+    /// use ascii_webcam::frame::AsciiFrame;
+    /// let text = AsciiFrame::new(4, 3).to_text(ColorMode::Mono);
+    /// assert_eq!(text.lines.len(), 3);
+    /// ```
+    #[must_use]
+    pub fn to_text(&self, color_mode: ColorMode) -> Text<'static> {
+        self.rows()
+            .map(|row| {
+                let mut spans = Vec::new();
+                let mut run = String::new();
+                let mut run_style = Style::default();
+
+                for cell in row {
+                    let style = cell.style(color_mode);
+                    if style != run_style && !run.is_empty() {
+                        spans.push(Span::styled(std::mem::take(&mut run), run_style));
+                    }
+                    run_style = style;
+                    run.push(cell.glyph);
+                }
+
+                if !run.is_empty() {
+                    spans.push(Span::styled(run, run_style));
+                }
+
+                Line::from(spans)
+            })
+            .collect::<Vec<Line>>()
+            .into()
+    }
+
+    /// Converts the frame to text colored with ANSI escape sequences, for terminals
+    /// and pagers such as `less -R`.
+    ///
+    /// As with `to_text`, consecutive cells with the same colors share one escape
+    /// sequence. Colors are reset at the end of every row, and in `Mono` mode the
+    /// result is the plain text of the frame.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use ascii_webcam::color::{ColorMode, Rgb};
+    /// use ascii_webcam::frame::{AsciiFrame, Cell};
+    /// let red = Cell::new('#', 255).with_fg(Rgb::new(255, 0, 0));
+    /// let frame = AsciiFrame::from_cells(2, 1, vec![red, red]);
+    /// assert_eq!(frame.to_ansi(ColorMode::Mono), "##");
+    /// assert_eq!(frame.to_ansi(ColorMode::Ansi256), "\x1b[38;5;196m##\x1b[0m");
+    /// ```
+    #[must_use]
+    pub fn to_ansi(&self, color_mode: ColorMode) -> String {
+        let mut ansi = String::new();
+        for (y, row) in self.rows().enumerate() {
+            if y > 0 {
+                ansi.push('\n');
+            }
+            let mut current = Vec::new();
+            for cell in row {
+                let sgr = cell.sgr(color_mode);
+                if sgr != current {
+                    if !current.is_empty() {
+                        ansi.push_str("\x1b[0m");
+                    }
+                    if !sgr.is_empty() {
+                        ansi.push_str(&format!("\x1b[{}m", sgr.join(";")));
+                    }
+                    current = sgr;
+                }
+                ansi.push(cell.glyph);
+            }
+            if !current.is_empty() {
+                ansi.push_str("\x1b[0m");
+            }
+        }
+        ansi
+    }
+}
+
+impl fmt::Display for AsciiFrame {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (y, row) in self.rows().enumerate() {
+            if y > 0 {
+                f.write_str("\n")?;
+            }
+            for cell in row {
+                write!(f, "{}", cell.glyph)?;
+            }
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,197 @@
+//! # Still Images
+//!
+//! This module provides `ImageSource`, a frame source showing a single image
+//! or a sequence of images, such as the files of a directory or the matches of
+//! a glob pattern, as a slideshow.
+
+use crate::error::{AppError, Result};
+use crate::source::{CapturedFrame, FrameSource, SourceProperties};
+use color_eyre::eyre::WrapErr;
+use opencv::{core::Mat, imgcodecs, prelude::*};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+/// The file extensions recognized as images.
+const IMAGE_EXTENSIONS: [&str; 7] = ["jpg", "jpeg", "png", "webp", "bmp", "tif", "tiff"];
+
+/// Returns `true` if the path has the extension of a supported image format.
+///
+/// # Examples
+///
+/// ```
+/// use ascii_webcam::image::is_image;
+/// assert!(is_image("assets/pexels-cat.JPG".as_ref()));
+/// assert!(!is_image("video.mp4".as_ref()));
+/// ```
+#[must_use]
+pub fn is_image(path: &Path) -> bool {
+    path.extension()
+        .and_then(|extension| extension.to_str())
+        .is_some_and(|extension| {
+            IMAGE_EXTENSIONS
+                .iter()
+                .any(|known| known.eq_ignore_ascii_case(extension))
+        })
+}
+
+/// Returns `true` if the path is a glob pattern rather than an existing file or directory.
+///
+/// # Examples
+///
+/// ```
+/// use ascii_webcam::image::is_pattern;
+/// assert!(is_pattern("assets/*.jpg".as_ref()));
+/// assert!(!is_pattern("assets".as_ref()));
+/// ```
+#[must_use]
+pub fn is_pattern(path: &Path) -> bool {
+    !path.exists() && path.to_string_lossy().contains(['*', '?', '['])
+}
+
+/// Loads an image as a BGR `Mat`.
+///
+/// # Errors
+///
+/// This function returns an `AppError::Source` error if the image cannot be read or decoded.
+pub fn load_image(path: &Path) -> Result<Mat> {
+    let image = imgcodecs::imread(&path.to_string_lossy(), imgcodecs::IMREAD_COLOR)
+        .wrap_err("failed to read image")?;
+    if image.empty() {
+        return Err(AppError::Source(format!("cannot read image {}", path.display())).into());
+    }
+    Ok(image)
+}
+
+/// A frame source showing a sequence of images, one after the other.
+pub struct ImageSource {
+    name: String,
+    paths: Vec<PathBuf>,
+    index: usize,
+    interval: Duration,
+    size: (i32, i32),
+}
+
+impl ImageSource {
+    /// The default time each image of a slideshow is shown.
+    pub const DEFAULT_INTERVAL: Duration = Duration::from_secs(5);
+
+    /// Opens an image file, or all images of a directory or glob pattern in name order.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use ascii_webcam::image::ImageSource;
+    /// let source = ImageSource::open("assets".as_ref()).unwrap();
+    /// assert_eq!(source.len(), 1);
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// This function may return an error if:
+    /// - The directory cannot be read
+    /// - The glob pattern is invalid
+    /// - The path is neither an image nor a directory or pattern matching images
+    pub fn open(path: &Path) -> Result<Self> {
+        let paths = if is_pattern(path) {
+            let pattern = path.to_string_lossy();
+            let mut paths = glob::glob(&pattern)
+                .map_err(|err| AppError::Source(format!("invalid pattern {pattern}: {err}")))?
+                .filter_map(std::result::Result::ok)
+                .filter(|path| path.is_file() && is_image(path))
+                .collect::<Vec<_>>();
+            paths.sort();
+            paths
+        } else if path.is_dir() {
+            let mut paths = fs::read_dir(path)
+                .wrap_err("failed to read image directory")?
+                .map(|entry| entry.map(|entry| entry.path()))
+                .collect::<std::io::Result<Vec<_>>>()
+                .wrap_err("failed to read image directory")?;
+            paths.retain(|path| path.is_file() && is_image(path));
+            paths.sort();
+            paths
+        } else {
+            vec![path.to_path_buf()]
+        };
+
+        let mut source = ImageSource::from_paths(paths)?;
+        source.name = path.display().to_string();
+        Ok(source)
+    }
+
+    /// Creates a source showing the given images in order.
+    ///
+    /// # Errors
+    ///
+    /// This function returns an `AppError::Source` error if `paths` is empty.
+    pub fn from_paths(paths: Vec<PathBuf>) -> Result<Self> {
+        let Some(first) = paths.first() else {
+            return Err(AppError::Source("no images found".to_string()).into());
+        };
+        Ok(ImageSource {
+            name: first.display().to_string(),
+            paths,
+            index: 0,
+            interval: ImageSource::DEFAULT_INTERVAL,
+            size: (0, 0),
+        })
+    }
+
+    /// Returns the source with the given time between images.
+    #[must_use]
+    pub fn with_interval(mut self, interval: Duration) -> Self {
+        self.interval = interval;
+        self
+    }
+
+    /// Returns the number of images.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.paths.len()
+    }
+
+    /// Returns `true` if the source has no images.
+    ///
+    /// Sources always hold at least one image, so this is only provided for completeness.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.paths.is_empty()
+    }
+}
+
+impl FrameSource for ImageSource {
+    fn next_frame(&mut self) -> Result<Option<CapturedFrame>> {
+        let Some(path) = self.paths.get(self.index) else {
+            return Ok(None);
+        };
+
+        let image = load_image(path)?;
+        self.size = (image.cols(), image.rows());
+        let timestamp = self.interval * u32::try_from(self.index).unwrap_or(u32::MAX);
+        self.index += 1;
+        Ok(Some(CapturedFrame { image, timestamp }))
+    }
+
+    fn properties(&self) -> SourceProperties {
+        SourceProperties {
+            name: self.name.clone(),
+            width: self.size.0,
+            height: self.size.1,
+            fps: Some(1.0 / self.interval.as_secs_f64().max(f64::EPSILON)),
+            frame_count: u64::try_from(self.paths.len()).ok(),
+            format: None,
+        }
+    }
+
+    fn seek(&mut self, position: Duration) -> Result<bool> {
+        // Positions are rounded to the nearest image, so that relative seeks by one
+        // interval land on the neighbouring image despite rounding errors.
+        let interval = self.interval.as_nanos().max(1);
+        let index = (position.as_nanos() + interval / 2) / interval;
+        self.index = usize::try_from(index)
+            .unwrap_or(usize::MAX)
+            .min(self.paths.len() - 1);
+        Ok(true)
+    }
+}
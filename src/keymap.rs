@@ -0,0 +1,450 @@
+//! # Keymap
+//!
+//! This module maps keys to the actions of the application. Every action has a
+//! default key, which the `[keys]` table of the configuration file can change:
+//!
+//! ```toml
+//! [keys]
+//! quit = "x"
+//! pause = "p"
+//! previous-frame = "backspace"
+//! ```
+//!
+//! The help popup and the instructions line are generated from the active keymap,
+//! so they always show the keys actually bound.
+
+use crate::error::{AppError, Result};
+use crossterm::event::KeyCode;
+use std::collections::{BTreeMap, HashMap};
+use std::fmt;
+
+/// The actions that can be bound to a key.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Action {
+    /// Quits the application.
+    Quit,
+    /// Shows or hides the help popup.
+    Help,
+    /// Toggles colors.
+    Color,
+    /// Switches to the next character ramp.
+    Ramp,
+    /// Inverts the character ramp.
+    InvertRamp,
+    /// Switches to the next render mode.
+    RenderMode,
+    /// Switches to the next dithering method.
+    Dither,
+    /// Switches to the next edge detector.
+    Edges,
+    /// Darkens the image.
+    Darker,
+    /// Brightens the image.
+    Brighter,
+    /// Lowers the contrast.
+    LessContrast,
+    /// Raises the contrast.
+    MoreContrast,
+    /// Lowers the gamma.
+    LowerGamma,
+    /// Raises the gamma.
+    HigherGamma,
+    /// Switches to the next histogram equalization method.
+    Equalization,
+    /// Resets the image adjustments.
+    ResetAdjustments,
+    /// Toggles auto-exposure.
+    AutoExposure,
+    /// Switches to the next fit mode.
+    Fit,
+    /// Makes the cells narrower.
+    NarrowerCells,
+    /// Makes the cells wider.
+    WiderCells,
+    /// Opens the camera picker.
+    CameraPicker,
+    /// Pauses or resumes playback.
+    Pause,
+    /// Shows the next frame while paused.
+    Step,
+    /// Toggles looping.
+    Loop,
+    /// Slows playback down.
+    Slower,
+    /// Speeds playback up.
+    Faster,
+    /// Shows the next image or frame.
+    NextFrame,
+    /// Shows the previous image or frame.
+    PreviousFrame,
+    /// Seeks 5 seconds back.
+    SeekBackward,
+    /// Seeks 5 seconds forward.
+    SeekForward,
+    /// Seeks 60 seconds back.
+    SeekBackwardLong,
+    /// Seeks 60 seconds forward.
+    SeekForwardLong,
+}
+
+impl Action {
+    /// All actions, in the order of their default keys in `Keymap::default`.
+    pub const ALL: [Action; 32] = [
+        Action::Quit,
+        Action::Help,
+        Action::Color,
+        Action::Ramp,
+        Action::InvertRamp,
+        Action::RenderMode,
+        Action::Dither,
+        Action::Edges,
+        Action::Darker,
+        Action::Brighter,
+        Action::LessContrast,
+        Action::MoreContrast,
+        Action::LowerGamma,
+        Action::HigherGamma,
+        Action::Equalization,
+        Action::ResetAdjustments,
+        Action::AutoExposure,
+        Action::Fit,
+        Action::NarrowerCells,
+        Action::WiderCells,
+        Action::CameraPicker,
+        Action::Pause,
+        Action::Step,
+        Action::Loop,
+        Action::Slower,
+        Action::Faster,
+        Action::NextFrame,
+        Action::PreviousFrame,
+        Action::SeekBackward,
+        Action::SeekForward,
+        Action::SeekBackwardLong,
+        Action::SeekForwardLong,
+    ];
+
+    /// Returns the action with the given name, as used in the configuration file.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use ascii_webcam::keymap::Action;
+    /// assert_eq!(Action::from_name("invert-ramp"), Some(Action::InvertRamp));
+    /// assert_eq!(Action::from_name("jump"), None);
+    /// ```
+    #[must_use]
+    pub fn from_name(name: &str) -> Option<Action> {
+        Action::ALL
+            .into_iter()
+            .find(|action| action.to_string() == name)
+    }
+
+    /// Returns the key bound to the action when nothing else is configured.
+    #[must_use]
+    pub fn default_key(self) -> KeyCode {
+        match self {
+            Action::Quit => KeyCode::Char('q'),
+            Action::Help => KeyCode::Char('?'),
+            Action::Color => KeyCode::Char('c'),
+            Action::Ramp => KeyCode::Char('r'),
+            Action::InvertRamp => KeyCode::Char('i'),
+            Action::RenderMode => KeyCode::Char('m'),
+            Action::Dither => KeyCode::Char('d'),
+            Action::Edges => KeyCode::Char('e'),
+            Action::Darker => KeyCode::Char('b'),
+            Action::Brighter => KeyCode::Char('B'),
+            Action::LessContrast => KeyCode::Char('k'),
+            Action::MoreContrast => KeyCode::Char('K'),
+            Action::LowerGamma => KeyCode::Char('g'),
+            Action::HigherGamma => KeyCode::Char('G'),
+            Action::Equalization => KeyCode::Char('h'),
+            Action::ResetAdjustments => KeyCode::Char('0'),
+            Action::AutoExposure => KeyCode::Char('a'),
+            Action::Fit => KeyCode::Char('f'),
+            Action::NarrowerCells => KeyCode::Char('['),
+            Action::WiderCells => KeyCode::Char(']'),
+            Action::CameraPicker => KeyCode::Char('v'),
+            Action::Pause => KeyCode::Char(' '),
+            Action::Step => KeyCode::Char('.'),
+            Action::Loop => KeyCode::Char('l'),
+            Action::Slower => KeyCode::Char('<'),
+            Action::Faster => KeyCode::Char('>'),
+            Action::NextFrame => KeyCode::Char('n'),
+            Action::PreviousFrame => KeyCode::Char('p'),
+            Action::SeekBackward => KeyCode::Left,
+            Action::SeekForward => KeyCode::Right,
+            Action::SeekBackwardLong => KeyCode::Down,
+            Action::SeekForwardLong => KeyCode::Up,
+        }
+    }
+}
+
+impl fmt::Display for Action {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Action::Quit => "quit",
+            Action::Help => "help",
+            Action::Color => "color",
+            Action::Ramp => "ramp",
+            Action::InvertRamp => "invert-ramp",
+            Action::RenderMode => "render-mode",
+            Action::Dither => "dither",
+            Action::Edges => "edges",
+            Action::Darker => "darker",
+            Action::Brighter => "brighter",
+            Action::LessContrast => "less-contrast",
+            Action::MoreContrast => "more-contrast",
+            Action::LowerGamma => "lower-gamma",
+            Action::HigherGamma => "higher-gamma",
+            Action::Equalization => "equalization",
+            Action::ResetAdjustments => "reset-adjustments",
+            Action::AutoExposure => "auto-exposure",
+            Action::Fit => "fit",
+            Action::NarrowerCells => "narrower-cells",
+            Action::WiderCells => "wider-cells",
+            Action::CameraPicker => "camera-picker",
+            Action::Pause => "pause",
+            Action::Step => "step",
+            Action::Loop => "loop",
+            Action::Slower => "slower",
+            Action::Faster => "faster",
+            Action::NextFrame => "next-frame",
+            Action::PreviousFrame => "previous-frame",
+            Action::SeekBackward => "seek-backward",
+            Action::SeekForward => "seek-forward",
+            Action::SeekBackwardLong => "seek-backward-long",
+            Action::SeekForwardLong => "seek-forward-long",
+        };
+        f.write_str(name)
+    }
+}
+
+/// The lines of the help popup: the actions described together, and what they do.
+pub const HELP: [(&[Action], &str); 24] = [
+    (&[Action::Quit], "quit the application"),
+    (&[Action::Help], "toggle this help menu"),
+    (&[Action::Color], "toggle colors"),
+    (&[Action::Ramp], "switch the character ramp"),
+    (&[Action::InvertRamp], "invert the character ramp"),
+    (&[Action::RenderMode], "switch the render mode"),
+    (&[Action::Dither], "switch the dithering method"),
+    (&[Action::Edges], "switch the edge detector"),
+    (
+        &[Action::Darker, Action::Brighter],
+        "darken / brighten the image",
+    ),
+    (
+        &[Action::LessContrast, Action::MoreContrast],
+        "lower / raise the contrast",
+    ),
+    (
+        &[Action::LowerGamma, Action::HigherGamma],
+        "lower / raise the gamma",
+    ),
+    (&[Action::Equalization], "switch the histogram equalization"),
+    (&[Action::ResetAdjustments], "reset the image adjustments"),
+    (&[Action::AutoExposure], "toggle auto-exposure"),
+    (&[Action::Fit], "switch between letterbox, fit and fill"),
+    (
+        &[Action::NarrowerCells, Action::WiderCells],
+        "make the cells narrower / wider",
+    ),
+    (&[Action::CameraPicker], "switch to another camera"),
+    (&[Action::Pause], "pause or resume a video"),
+    (&[Action::Step], "step one frame"),
+    (
+        &[Action::SeekBackward, Action::SeekForward],
+        "seek 5 seconds back / forward",
+    ),
+    (
+        &[Action::SeekBackwardLong, Action::SeekForwardLong],
+        "seek 60 seconds back / forward",
+    ),
+    (
+        &[Action::NextFrame, Action::PreviousFrame],
+        "show the next / previous image or frame",
+    ),
+    (
+        &[Action::Slower, Action::Faster],
+        "slow down / speed up playback",
+    ),
+    (&[Action::Loop], "toggle looping"),
+];
+
+/// Parses the name of a key, as used in the configuration file.
+///
+/// Single characters stand for themselves, case included. Other keys are named
+/// `space`, `enter`, `esc`, `tab`, `backspace`, `delete`, `insert`, `home`, `end`,
+/// `pageup`, `pagedown`, `left`, `right`, `up`, `down` and `f1` to `f12`, in any case.
+///
+/// # Examples
+///
+/// ```
+/// use ascii_webcam::keymap::parse_key;
+/// use crossterm::event::KeyCode;
+/// assert_eq!(parse_key("X").unwrap(), KeyCode::Char('X'));
+/// assert_eq!(parse_key("PageUp").unwrap(), KeyCode::PageUp);
+/// assert_eq!(parse_key("f5").unwrap(), KeyCode::F(5));
+/// assert!(parse_key("hyper").is_err());
+/// ```
+///
+/// # Errors
+///
+/// This function returns an `AppError::Config` error if the key is unknown.
+pub fn parse_key(name: &str) -> Result<KeyCode> {
+    let mut chars = name.chars();
+    if let (Some(key), None) = (chars.next(), chars.next()) {
+        return Ok(KeyCode::Char(key));
+    }
+
+    let key = match name.to_ascii_lowercase().as_str() {
+        "space" => KeyCode::Char(' '),
+        "enter" => KeyCode::Enter,
+        "esc" | "escape" => KeyCode::Esc,
+        "tab" => KeyCode::Tab,
+        "backspace" => KeyCode::Backspace,
+        "delete" => KeyCode::Delete,
+        "insert" => KeyCode::Insert,
+        "home" => KeyCode::Home,
+        "end" => KeyCode::End,
+        "pageup" => KeyCode::PageUp,
+        "pagedown" => KeyCode::PageDown,
+        "left" => KeyCode::Left,
+        "right" => KeyCode::Right,
+        "up" => KeyCode::Up,
+        "down" => KeyCode::Down,
+        other => match other
+            .strip_prefix('f')
+            .and_then(|number| number.parse().ok())
+        {
+            Some(number @ 1..=12) => KeyCode::F(number),
+            _ => return Err(AppError::Config(format!("unknown key {name}")).into()),
+        },
+    };
+    Ok(key)
+}
+
+/// Returns the label of a key, as shown in the help popup.
+#[must_use]
+pub fn key_label(key: KeyCode) -> String {
+    match key {
+        KeyCode::Char(' ') => "Space".to_string(),
+        KeyCode::Char(key) => key.to_string(),
+        KeyCode::Left => "←".to_string(),
+        KeyCode::Right => "→".to_string(),
+        KeyCode::Up => "↑".to_string(),
+        KeyCode::Down => "↓".to_string(),
+        KeyCode::PageUp => "PgUp".to_string(),
+        KeyCode::PageDown => "PgDn".to_string(),
+        KeyCode::F(number) => format!("F{number}"),
+        other => format!("{other:?}"),
+    }
+}
+
+/// The keys bound to each action.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Keymap {
+    bindings: Vec<(KeyCode, Action)>,
+}
+
+impl Default for Keymap {
+    fn default() -> Self {
+        Keymap {
+            bindings: Action::ALL
+                .into_iter()
+                .map(|action| (action.default_key(), action))
+                .collect(),
+        }
+    }
+}
+
+impl Keymap {
+    /// Returns the default keymap with some actions bound to other keys.
+    ///
+    /// `overrides` maps action names to key names. An action bound to a new key
+    /// loses its default key, and an action whose key is taken loses it. An empty
+    /// key name leaves the action unbound, except for `quit`, which must keep a key.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use ascii_webcam::keymap::{Action, Keymap};
+    /// use crossterm::event::KeyCode;
+    /// use std::collections::BTreeMap;
+    /// let overrides = BTreeMap::from([("quit".to_string(), "x".to_string())]);
+    /// let keymap = Keymap::with_overrides(&overrides).unwrap();
+    /// assert_eq!(keymap.action(KeyCode::Char('x')), Some(Action::Quit));
+    /// assert_eq!(keymap.action(KeyCode::Char('q')), None);
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// This function returns an `AppError::Config` error if an action or key is
+    /// unknown, if two actions are bound to the same key, or if `quit` is left
+    /// without a key.
+    pub fn with_overrides(overrides: &BTreeMap<String, String>) -> Result<Keymap> {
+        let mut keymap = Keymap::default();
+        let mut overridden: HashMap<KeyCode, &str> = HashMap::new();
+        for (name, key) in overrides {
+            let action = Action::from_name(name)
+                .ok_or_else(|| AppError::Config(format!("unknown action {name}")))?;
+            keymap.bindings.retain(|&(_, bound)| bound != action);
+            if !key.is_empty() {
+                let code = parse_key(key)?;
+                if let Some(other) = overridden.insert(code, name) {
+                    return Err(AppError::Config(format!(
+                        "key {key} is bound to both {other} and {name}"
+                    ))
+                    .into());
+                }
+                keymap.bindings.retain(|&(bound, _)| bound != code);
+                keymap.bindings.push((code, action));
+            }
+        }
+        if !keymap
+            .bindings
+            .iter()
+            .any(|&(_, bound)| bound == Action::Quit)
+        {
+            return Err(AppError::Config("quit must be bound to a key".to_string()).into());
+        }
+        Ok(keymap)
+    }
+
+    /// Returns the action bound to a key, if any.
+    #[must_use]
+    pub fn action(&self, key: KeyCode) -> Option<Action> {
+        self.bindings
+            .iter()
+            .find(|&&(bound, _)| bound == key)
+            .map(|&(_, action)| action)
+    }
+
+    /// Returns the label of the keys bound to an action, or `-` if it is unbound.
+    #[must_use]
+    pub fn label(&self, action: Action) -> String {
+        let keys = self
+            .bindings
+            .iter()
+            .filter(|&&(_, bound)| bound == action)
+            .map(|&(key, _)| key_label(key))
+            .collect::<Vec<_>>();
+        if keys.is_empty() {
+            "-".to_string()
+        } else {
+            keys.join(",")
+        }
+    }
+
+    /// Returns the label of the keys bound to several actions described together,
+    /// such as `b / B`.
+    #[must_use]
+    pub fn group_label(&self, actions: &[Action]) -> String {
+        actions
+            .iter()
+            .map(|&action| self.label(action))
+            .collect::<Vec<_>>()
+            .join(" / ")
+    }
+}
@@ -1,10 +1,34 @@
+pub mod adjust;
 pub mod app;
 pub mod ascii;
+pub mod aspect;
+pub mod blocks;
+pub mod braille;
+pub mod cli;
+pub mod color;
+pub mod config;
+pub mod devices;
+pub mod dither;
+pub mod edges;
 pub mod error;
+pub mod exposure;
+pub mod frame;
+pub mod image;
+pub mod keymap;
+pub mod playback;
+pub mod ramp;
+pub mod reconnect;
+pub mod shape;
+pub mod snapshot;
+pub mod source;
+pub mod stream;
+pub mod synthetic;
 pub mod terminal;
 pub mod video;
 
 // Re-export key types for convenience
 pub use app::App;
 pub use error::{AppError, Result};
+pub use frame::AsciiFrame;
+pub use source::FrameSource;
 pub use video::VideoCapture;
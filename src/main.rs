@@ -1,48 +1,145 @@
 //! # ASCII Webcam Application
 //!
 //! This is the main entry point for the ASCII Webcam application.
-//! It sets up the terminal, initializes the camera, and runs the main application loop.
+//! It parses the command line, sets up the terminal, opens the camera or another
+//! source, and runs the main application loop. See `--help` for the options.
 
-use crate::app::App;
-use crate::error::Result;
-use crate::terminal::{reset_terminal, setup_terminal};
-use crate::video::VideoCapture;
+use ascii_webcam::{
+    aspect::detect_cell_aspect,
+    cli::Cli,
+    color::ColorMode,
+    config::{Config, ConfigWatcher},
+    devices::{list_cameras, probe_resolutions, DevicePicker},
+    error::install_hooks,
+    keymap::Action,
+    playback::{Playback, PlaybackClock},
+    reconnect::{Connection, Reconnector},
+    snapshot::SnapshotFormat,
+    source::{open_path, SourceProperties},
+    terminal::{reset_terminal, setup_terminal},
+    video::CaptureFormat,
+    App, AppError, FrameSource, Result, VideoCapture,
+};
+use clap::Parser;
 use color_eyre::eyre::WrapErr;
-use crossbeam_channel::{bounded, select};
+use crossbeam_channel::{after, bounded, never, select, unbounded, Sender};
 use crossterm::event::{self, Event, KeyCode};
-use ratatui::Terminal;
+use opencv::core::Mat;
+use ratatui::{layout::Rect, Terminal};
+use std::fs::File;
+use std::io::{self, BufWriter, IsTerminal, Write};
+use std::path::Path;
 use std::sync::{Arc, Mutex};
 use std::thread;
 use std::time::{Duration, Instant};
 
-mod app;
-mod ascii;
-mod error;
-mod terminal;
-mod video;
+/// How often the capture thread checks for commands while paused or ended
+const IDLE_INTERVAL: Duration = Duration::from_millis(10);
+/// The size of headless frames in cells, unless set with `--size` or written to a terminal
+const HEADLESS_SIZE: (u16, u16) = (80, 24);
 
-/// Target frames per second for the application
-const TARGET_FPS: u64 = 30;
-/// Size of the circular buffer used for FPS calculation
-const FPS_BUFFER_SIZE: usize = 120;
+/// What the capture thread reports to the UI.
+enum CaptureEvent {
+    /// A frame is due to be shown.
+    Frame(Mat),
+    /// The camera stopped delivering frames and is being reopened.
+    Lost(Connection),
+    /// The camera delivers frames again.
+    Reconnected,
+    /// The source has no more frames, until it is looped or seeked.
+    Ended,
+    /// The camera could not be reopened, which ends the application.
+    Failed(AppError),
+}
 
 /// The main function of the application.
 ///
 /// It performs the following steps:
-/// 1. Installs `color_eyre` for error handling
-/// 2. Sets up the terminal
-/// 3. Initializes the camera
-/// 4. Runs the main application loop
-/// 5. Resets the terminal before exiting
+/// 1. Parses the command line and installs `color_eyre` for error handling, with
+///    backtraces if asked for
+/// 2. Reads the configuration file, whose settings the command line overrides
+/// 3. Opens the source given as argument, or the camera
+/// 4. Sets up the terminal, unless running headless
+/// 5. Runs the main application loop
+/// 6. Resets the terminal before exiting
 fn main() -> Result<()> {
-    color_eyre::install()?;
-    let mut terminal = setup_terminal().wrap_err("failed to setup terminal")?;
-    let camera = Arc::new(Mutex::new(
-        VideoCapture::new(0).wrap_err("failed to initialize camera")?,
-    ));
+    let cli = Cli::try_parse();
+    install_hooks(cli.as_ref().is_ok_and(|cli| cli.verbose > 1))?;
+    let cli = match cli {
+        Ok(cli) => cli,
+        // Help and version are not errors
+        Err(err) if !err.use_stderr() => err.exit(),
+        Err(err) => {
+            let message = err.render().to_string();
+            let message = message.trim_start_matches("error: ").trim_end();
+            return Err(AppError::Config(message.to_string()).into());
+        }
+    };
+    if let Some(shell) = cli.completions {
+        Cli::write_completions(shell, &mut io::stdout());
+        return Ok(());
+    }
+    if cli.list_devices {
+        print_devices();
+        return Ok(());
+    }
+
+    let config_path = Config::locate(cli.config.as_deref());
+    let config = config_path
+        .as_deref()
+        .map(Config::load)
+        .transpose()?
+        .unwrap_or_default();
+    let settings = config.merge(&cli)?;
+    let format = settings.capture_format()?;
     let mut app = App::new();
+    settings.apply(&mut app)?;
+    app.keymap = config.keymap()?;
+
+    let (source, camera): (Box<dyn FrameSource>, _) = match (&settings.source, settings.camera) {
+        (Some(path), _) => (open_path(path).wrap_err("failed to open source")?, None),
+        (None, index) => {
+            let index = index.unwrap_or(0);
+            (
+                Box::new(
+                    VideoCapture::open(index, &format).wrap_err("failed to initialize camera")?,
+                ),
+                Some(index),
+            )
+        }
+    };
+    if cli.verbose > 0 {
+        if let Some(path) = &config_path {
+            eprintln!("Configuration: {}", path.display());
+        }
+        eprintln!("Source: {}", source.properties());
+        if camera.is_some() {
+            eprintln!("Requested capture mode: {format}");
+        }
+    }
+
+    if cli.snapshot {
+        return run_snapshot(&mut app, source, &settings);
+    }
+    if cli.headless {
+        return run_headless(&mut app, source, &settings);
+    }
+
+    let source = Arc::new(Mutex::new(source));
+    let mut terminal = setup_terminal().wrap_err("failed to setup terminal")?;
+    if let Some(cell_aspect) = detect_cell_aspect() {
+        app.cell_aspect = cell_aspect;
+    }
 
-    let res = run_app(&mut terminal, &mut app, camera);
+    let res = run_app(
+        &mut terminal,
+        &mut app,
+        source,
+        camera,
+        &cli,
+        config_path.as_deref(),
+        settings,
+    );
 
     reset_terminal().wrap_err("failed to reset terminal")?;
     res
@@ -51,32 +148,48 @@ fn main() -> Result<()> {
 /// Runs the main application loop.
 ///
 /// This function is responsible for:
-/// - Setting up multi-threaded frame capture and event handling
+/// - Setting up multi-threaded frame capture from any `FrameSource` and event handling
 /// - Updating the application state
 /// - Rendering frames
-/// - Handling user input
-/// - Maintaining the target frame rate
-/// - Calculating a stable FPS using a circular buffer
+/// - Handling user input, including playback control and switching cameras
+/// - Drawing at most `--fps` frames per second, or the rate of the source
+/// - Measuring the number of frames drawn per second
+///
+/// `camera` is the index of the camera in use, if the source is a camera. `cli`
+/// holds the command-line options and `settings` the same options completed by
+/// the configuration file at `config_path`, which is reloaded whenever it changes.
 fn run_app<B: ratatui::backend::Backend>(
     terminal: &mut Terminal<B>,
     app: &mut App,
-    camera: Arc<Mutex<VideoCapture>>,
+    source: Arc<Mutex<Box<dyn FrameSource>>>,
+    mut camera: Option<i32>,
+    cli: &Cli,
+    config_path: Option<&Path>,
+    mut settings: Cli,
 ) -> Result<()> {
+    // Cameras switched to are asked for the capture mode chosen on startup
+    let format = settings.capture_format()?;
+    // Running without reloading is better than not running, so watching is optional
+    let watcher = config_path.and_then(|path| ConfigWatcher::new(path).ok());
+    let config_changes = watcher
+        .as_ref()
+        .map_or_else(never, |watcher| watcher.changes().clone());
+
     // Set up channels for communication between threads
-    let (frame_sender, frame_receiver) = bounded(2);
+    let (frame_sender, mut frame_receiver) = bounded(2);
     let (event_sender, event_receiver) = bounded(10);
+    let (camera_sender, camera_receiver) = unbounded();
+
+    let mut properties = source
+        .lock()
+        .map(|source| source.properties())
+        .unwrap_or_default();
+    let playback = Arc::new(Mutex::new(Playback::new(properties.duration())));
 
     // Spawn frame capture thread
-    let camera_clone = Arc::clone(&camera);
-    thread::spawn(move || loop {
-        if let Ok(mut camera) = camera_clone.lock() {
-            if let Ok(frame) = camera.read_frame() {
-                if frame_sender.send(frame).is_err() {
-                    break;
-                }
-            }
-        }
-    });
+    let source_clone = Arc::clone(&source);
+    let playback_clone = Arc::clone(&playback);
+    thread::spawn(move || capture_frames(&source_clone, &playback_clone, &frame_sender));
 
     // Spawn event handling thread
     thread::spawn(move || loop {
@@ -89,53 +202,592 @@ fn run_app<B: ratatui::backend::Backend>(
         }
     });
 
-    // Initialize FPS calculation buffer
-    let mut fps_buffer = vec![Duration::from_secs(1); FPS_BUFFER_SIZE];
-    let mut fps_index = 0;
-
-    let target_frame_time = Duration::from_micros(1_000_000 / TARGET_FPS);
+    let mut fps_counter = FpsCounter::new();
+    let mut last_frame: Option<Mat> = None;
+    let mut last_draw: Option<Instant> = None;
+    // Whether the last frame was skipped by the frame rate cap and is not drawn yet
+    let mut pending = false;
+    // Cameras may only settle on a capture mode once they deliver frames, so the
+    // properties shown in the Stats block are read again after the first frame
+    app.source = properties.clone();
+    let mut refresh_properties = true;
 
     loop {
-        let frame_start = Instant::now();
+        let source_fps = properties.fps.unwrap_or(0.0);
+        // The next and previous keys move by one frame, i.e. one image of a slideshow
+        let frame_step = if source_fps > 0.0 {
+            1.0 / source_fps
+        } else {
+            0.0
+        };
+        // Single images have nothing to play
+        let show_progress = properties.duration().is_some()
+            && properties.frame_count.is_some_and(|count| count > 1);
+        let progress = show_progress.then_some(&*playback);
+        let speed = playback.lock().map_or(1.0, |playback| playback.speed);
+        // Frames arriving faster than `--fps`, or than the source plays, are skipped,
+        // and the last one skipped is drawn once allowed, in case no other follows
+        let max_fps = settings
+            .fps
+            .or_else(|| (source_fps > 0.0).then_some(source_fps * speed));
+        let min_frame_time = max_fps.map(|fps| Duration::from_secs_f64(1.0 / fps));
+        let remaining = last_draw
+            .zip(min_frame_time)
+            .map_or(Duration::ZERO, |(drawn, min)| {
+                min.saturating_sub(drawn.elapsed())
+            });
+        let throttled = !remaining.is_zero();
+        let pending_timer = if pending { after(remaining) } else { never() };
 
         // Use select! macro to handle both frame processing and events
         select! {
-            recv(frame_receiver) -> frame => {
-                if let Ok(frame) = frame {
-                    let size = terminal.size().wrap_err("failed to get terminal size")?;
-                    let term_width = i32::from(size.width);
-                    let term_height = i32::from(size.height);
-                    app.update(&frame, term_width, term_height)
-                        .wrap_err("failed to update app state")?;
-
-                    terminal
-                        .draw(|f| app.render(f))
-                        .wrap_err("failed to render frame")?;
-
-                    // Update FPS calculation
-                    let frame_time = frame_start.elapsed();
-                    fps_buffer[fps_index] = frame_time;
-                    fps_index = (fps_index + 1) % FPS_BUFFER_SIZE;
-
-                    let avg_frame_time = fps_buffer.iter().sum::<Duration>() / FPS_BUFFER_SIZE as u32;
-                    app.fps = 1.0 / avg_frame_time.as_secs_f64();
+            recv(frame_receiver) -> capture => {
+                match capture {
+                    Ok(CaptureEvent::Frame(frame)) if throttled => {
+                        last_frame = Some(frame);
+                        pending = true;
+                    }
+                    Ok(CaptureEvent::Frame(frame)) => {
+                        if refresh_properties {
+                            // The capture thread may be reading, in which case the next frame will do
+                            if let Ok(source) = source.try_lock() {
+                                properties = source.properties();
+                                app.source = properties.clone();
+                                refresh_properties = false;
+                            }
+                        }
+                        // Time from the start of drawing, so that slow draws do not lower the rate
+                        last_draw = Some(Instant::now());
+                        draw_frame(terminal, app, Some(&frame), settings.size, progress)?;
+                        pending = false;
+                        app.fps = fps_counter.count().unwrap_or(app.fps);
+                        last_frame = Some(frame);
+                    }
+                    Ok(CaptureEvent::Lost(connection)) => {
+                        // The last good frame, if any, stays frozen under the overlay
+                        app.connection = connection;
+                        draw_frame(terminal, app, last_frame.as_ref(), settings.size, progress)?;
+                    }
+                    Ok(CaptureEvent::Reconnected) => {
+                        app.connection = Connection::Connected;
+                        refresh_properties = true;
+                    }
+                    Ok(CaptureEvent::Ended) => {
+                        // Show that playback has stopped
+                        draw_frame(terminal, app, last_frame.as_ref(), settings.size, progress)?;
+                    }
+                    Ok(CaptureEvent::Failed(err)) => return Err(err.into()),
+                    // The source has ended, so stop waiting for frames.
+                    Err(_) => frame_receiver = never(),
                 }
             }
+            recv(pending_timer) -> _ => {
+                last_draw = Some(Instant::now());
+                draw_frame(terminal, app, last_frame.as_ref(), settings.size, progress)?;
+                pending = false;
+                app.fps = fps_counter.count().unwrap_or(app.fps);
+            }
+            recv(camera_receiver) -> cameras => {
+                // The picker may have been closed while the cameras were listed
+                let searching = app.device_picker.as_ref().is_some_and(|picker| picker.searching);
+                if let (Ok(cameras), true) = (cameras, searching) {
+                    app.open_device_picker(cameras, camera);
+                    draw_frame(terminal, app, last_frame.as_ref(), settings.size, progress)?;
+                }
+            }
+            recv(config_changes) -> _ => {
+                // Editors often save in several steps, which are handled at once
+                while config_changes.try_recv().is_ok() {}
+                if let Some(path) = config_path {
+                    match reload_config(app, cli, &settings, path) {
+                        Ok(reloaded) => {
+                            settings = reloaded;
+                            app.config_error = None;
+                        }
+                        // Keep the current settings until the file is fixed
+                        Err(err) => app.config_error = Some(format!("{err:#}")),
+                    }
+                }
+                draw_frame(terminal, app, last_frame.as_ref(), settings.size, progress)?;
+            }
             recv(event_receiver) -> event => {
-                if let Ok(Event::Key(key)) = event {
-                    match key.code {
-                        KeyCode::Char('q') => return Ok(()),
-                        KeyCode::Char('?') => app.toggle_help(),
-                        _ => {}
+                match &event {
+                    Ok(Event::Key(key)) if app.device_picker.is_some() => match key.code {
+                        KeyCode::Up => app.device_picker.iter_mut().for_each(DevicePicker::previous),
+                        KeyCode::Down => app.device_picker.iter_mut().for_each(DevicePicker::next),
+                        KeyCode::Enter => {
+                            let selected = app
+                                .device_picker
+                                .as_ref()
+                                .and_then(DevicePicker::selected)
+                                .map(|selected| selected.index);
+                            match selected {
+                                Some(index) if camera != Some(index) => {
+                                    match switch_camera(&source, index, &format) {
+                                        Ok(switched) => {
+                                            properties = switched;
+                                            app.source = properties.clone();
+                                            refresh_properties = true;
+                                            camera = Some(index);
+                                            control(&playback, |playback| {
+                                                *playback = Playback::new(properties.duration());
+                                            });
+                                            app.close_device_picker();
+                                        }
+                                        Err(err) => {
+                                            // Keep the current source and let the user pick another camera.
+                                            if let Some(picker) = &mut app.device_picker {
+                                                picker.error = Some(err.to_string());
+                                            }
+                                        }
+                                    }
+                                }
+                                _ => app.close_device_picker(),
+                            }
+                        }
+                        KeyCode::Esc => app.close_device_picker(),
+                        code => match app.keymap.action(code) {
+                            Some(Action::Quit) => return Ok(()),
+                            Some(Action::CameraPicker) => app.close_device_picker(),
+                            _ => {}
+                        },
+                    },
+                    Ok(Event::Key(key)) => match app.keymap.action(key.code) {
+                        Some(Action::Quit) => return Ok(()),
+                        Some(Action::Help) => app.toggle_help(),
+                        Some(Action::Color) => app.toggle_color(),
+                        Some(Action::Ramp) => app.next_ramp(),
+                        Some(Action::InvertRamp) => app.invert_ramp(),
+                        Some(Action::RenderMode) => app.next_render_mode(),
+                        Some(Action::Dither) => app.next_dither(),
+                        Some(Action::Edges) => app.next_edge_mode(),
+                        Some(Action::Darker) => app.adjust_brightness(false),
+                        Some(Action::Brighter) => app.adjust_brightness(true),
+                        Some(Action::LessContrast) => app.adjust_contrast(false),
+                        Some(Action::MoreContrast) => app.adjust_contrast(true),
+                        Some(Action::LowerGamma) => app.adjust_gamma(false),
+                        Some(Action::HigherGamma) => app.adjust_gamma(true),
+                        Some(Action::Equalization) => app.next_equalization(),
+                        Some(Action::ResetAdjustments) => app.reset_adjustments(),
+                        Some(Action::AutoExposure) => app.toggle_auto_exposure(),
+                        Some(Action::Fit) => app.next_fit_mode(),
+                        Some(Action::NarrowerCells) => app.adjust_cell_aspect(false),
+                        Some(Action::WiderCells) => app.adjust_cell_aspect(true),
+                        Some(Action::CameraPicker) => {
+                            // Probing cameras can take seconds, which would block input and drawing
+                            app.device_picker = Some(DevicePicker::searching());
+                            let sender = camera_sender.clone();
+                            thread::spawn(move || sender.send(list_cameras(camera)));
+                        }
+                        Some(Action::Pause) => control(&playback, Playback::toggle_pause),
+                        Some(Action::Step) => control(&playback, Playback::step),
+                        Some(Action::Loop) => control(&playback, Playback::toggle_loop),
+                        Some(Action::Faster) => control(&playback, Playback::faster),
+                        Some(Action::Slower) => control(&playback, Playback::slower),
+                        Some(Action::NextFrame) => control(&playback, |playback| playback.seek_by(frame_step)),
+                        Some(Action::PreviousFrame) => control(&playback, |playback| playback.seek_by(-frame_step)),
+                        Some(Action::SeekBackward) => control(&playback, |playback| playback.seek_by(-5.0)),
+                        Some(Action::SeekForward) => control(&playback, |playback| playback.seek_by(5.0)),
+                        Some(Action::SeekBackwardLong) => control(&playback, |playback| playback.seek_by(-60.0)),
+                        Some(Action::SeekForwardLong) => control(&playback, |playback| playback.seek_by(60.0)),
+                        None => {}
+                    },
+                    _ => {}
+                }
+
+                // Redraw the last frame right away, so that changes also show on still
+                // images and paused videos, and the frame fits a resized terminal
+                if matches!(event, Ok(Event::Key(_) | Event::Resize(_, _))) {
+                    draw_frame(terminal, app, last_frame.as_ref(), settings.size, progress)?;
+                }
+            }
+        }
+    }
+}
+
+/// Measures the number of frames drawn per second, over periods of one second.
+struct FpsCounter {
+    /// When the current period started.
+    start: Instant,
+    /// The number of frames drawn in the current period.
+    frames: u32,
+}
+
+impl FpsCounter {
+    /// Creates a counter whose first period starts now.
+    fn new() -> Self {
+        FpsCounter {
+            start: Instant::now(),
+            frames: 0,
+        }
+    }
+
+    /// Counts a drawn frame.
+    ///
+    /// # Returns
+    ///
+    /// Returns the number of frames drawn per second once a period is over, and
+    /// starts the next one.
+    fn count(&mut self) -> Option<f64> {
+        self.frames += 1;
+        let elapsed = self.start.elapsed();
+        if elapsed < Duration::from_secs(1) {
+            return None;
+        }
+        let fps = f64::from(self.frames) / elapsed.as_secs_f64();
+        *self = FpsCounter::new();
+        Some(fps)
+    }
+}
+
+/// Prints the cameras connected to the machine and the resolutions they support.
+fn print_devices() {
+    let cameras = list_cameras(None);
+    if cameras.is_empty() {
+        println!("No camera found");
+    }
+    for camera in cameras {
+        let resolutions = probe_resolutions(camera.index)
+            .unwrap_or_default()
+            .iter()
+            .map(|(width, height)| format!("{width}x{height}"))
+            .collect::<Vec<_>>();
+        if resolutions.is_empty() {
+            println!("{camera}");
+        } else {
+            println!("{camera}: {}", resolutions.join(", "));
+        }
+    }
+}
+
+/// Opens a camera and makes it the source of the capture thread.
+///
+/// The current source is kept if the camera cannot be opened.
+fn switch_camera(
+    source: &Mutex<Box<dyn FrameSource>>,
+    index: i32,
+    format: &CaptureFormat,
+) -> Result<SourceProperties> {
+    let capture = VideoCapture::open(index, format)?;
+    let properties = capture.properties();
+    if let Ok(mut source) = source.lock() {
+        *source = Box::new(capture);
+    }
+    Ok(properties)
+}
+
+/// Reads the configuration file again and applies its keys and the display
+/// settings that differ from `previous`, the options completed by the last load.
+///
+/// # Returns
+///
+/// Returns the command-line options completed by the new configuration.
+fn reload_config(app: &mut App, cli: &Cli, previous: &Cli, path: &Path) -> Result<Cli> {
+    let config = Config::load(path)?;
+    let settings = config.merge(cli)?;
+    let keymap = config.keymap()?;
+    settings.apply_changes(previous, app)?;
+    app.keymap = keymap;
+    Ok(settings)
+}
+
+/// Applies a playback command from the UI.
+fn control(playback: &Mutex<Playback>, command: impl FnOnce(&mut Playback)) {
+    if let Ok(mut playback) = playback.lock() {
+        command(&mut playback);
+    }
+}
+
+/// Reads frames from the source and sends them when they are due.
+///
+/// The capture thread follows the playback state: it holds still while paused,
+/// seeks on request and restarts looping sources at their end. Once a source has
+/// ended, it waits for a seek instead of stopping, so that playback can resume.
+///
+/// A camera failing to deliver frames several times in a row is reopened with an
+/// increasing delay between attempts, and reported as failed once none succeeds.
+fn capture_frames(
+    source: &Mutex<Box<dyn FrameSource>>,
+    playback: &Mutex<Playback>,
+    sender: &Sender<CaptureEvent>,
+) {
+    let mut clock = PlaybackClock::default();
+    let mut reconnector = Reconnector::default();
+    loop {
+        let Ok(mut state) = playback.lock() else {
+            break;
+        };
+        let seek = state.take_seek();
+        let step = state.take_step();
+        let (paused, speed, looping, ended) =
+            (state.paused, state.speed, state.looping, state.ended);
+        drop(state);
+
+        if let Some(position) = seek {
+            if let Ok(mut source) = source.lock() {
+                let _ = source.seek(position);
+            }
+            clock.reset();
+        } else if (paused && !step) || (ended && !looping) {
+            clock.reset();
+            thread::sleep(IDLE_INTERVAL);
+            continue;
+        }
+
+        let next = match source.lock() {
+            Ok(mut source) => source.next_frame(),
+            Err(_) => break,
+        };
+        match next {
+            Ok(Some(frame)) => {
+                // Sources such as files are read faster than real time, so wait until
+                // each frame is due. The clock was reset for seeks and steps, so they
+                // are shown right away and the following frames are timed from them.
+                let due = clock.due(frame.timestamp, speed);
+                if let Some(wait) = due.checked_duration_since(Instant::now()) {
+                    thread::sleep(wait);
+                }
+                if let Ok(mut state) = playback.lock() {
+                    state.position = frame.timestamp;
+                    state.ended = false;
+                }
+                if reconnector.record_success() && sender.send(CaptureEvent::Reconnected).is_err() {
+                    break;
+                }
+                if sender.send(CaptureEvent::Frame(frame.image)).is_err() {
+                    break;
+                }
+            }
+            Ok(None) => {
+                let restarted = looping
+                    && source
+                        .lock()
+                        .is_ok_and(|mut source| source.seek(Duration::ZERO).unwrap_or(false));
+                if !restarted {
+                    // The last frame stays on screen once the source has ended.
+                    let newly_ended = playback
+                        .lock()
+                        .is_ok_and(|mut state| !std::mem::replace(&mut state.ended, true));
+                    if newly_ended && sender.send(CaptureEvent::Ended).is_err() {
+                        break;
+                    }
+                }
+                clock.reset();
+            }
+            Err(err) => {
+                if !reconnector.record_failure() {
+                    // Occasional read errors are skipped, but without spinning on a failing source.
+                    thread::sleep(IDLE_INTERVAL);
+                    continue;
+                }
+                // Back off after every attempt without a frame, whether the camera could
+                // not be reopened or was reopened but still delivers nothing.
+                if reconnector.attempts() > 0 {
+                    thread::sleep(reconnector.delay());
+                }
+                let Some(connection) = reconnector.start_attempt() else {
+                    let message = format!(
+                        "camera lost after {} reconnection attempts: {err}",
+                        reconnector.attempts()
+                    );
+                    let _ = sender.send(CaptureEvent::Failed(AppError::Camera(message)));
+                    break;
+                };
+                let reconnected = match source.lock() {
+                    Ok(mut source) => source.reconnect(),
+                    Err(_) => break,
+                };
+                match reconnected {
+                    // Files and pipes cannot be reopened, so their errors keep being skipped.
+                    Ok(false) => {
+                        reconnector = Reconnector::default();
+                        thread::sleep(IDLE_INTERVAL);
+                    }
+                    // The next read tells whether the camera is back.
+                    Ok(true) | Err(_) => {
+                        if sender.send(CaptureEvent::Lost(connection)).is_err() {
+                            break;
+                        }
                     }
                 }
             }
         }
+    }
+}
 
-        // Maintain target frame rate
-        let processing_time = frame_start.elapsed();
-        if processing_time < target_frame_time {
-            thread::sleep(target_frame_time - processing_time);
+/// Converts a frame to the size of the ASCII block, or `max_size` if smaller, and
+/// draws the UI. Without a frame, such as before the first one arrives, only the UI
+/// and its overlays are drawn.
+///
+/// The playback progress is read right before drawing, so that pausing, seeking
+/// and the end of the source show even when no new frame arrives.
+fn draw_frame<B: ratatui::backend::Backend>(
+    terminal: &mut Terminal<B>,
+    app: &mut App,
+    frame: Option<&Mat>,
+    max_size: Option<(u16, u16)>,
+    progress: Option<&Mutex<Playback>>,
+) -> Result<()> {
+    app.playback =
+        progress.and_then(|playback| playback.lock().ok().map(|playback| playback.clone()));
+    let size = terminal.size().wrap_err("failed to get terminal size")?;
+    let area = app.frame_area(Rect::new(0, 0, size.width, size.height));
+    let (width, height) = max_size.map_or((area.width, area.height), |(width, height)| {
+        (area.width.min(width), area.height.min(height))
+    });
+    if let Some(frame) = frame {
+        app.update(frame, i32::from(width), i32::from(height))
+            .wrap_err("failed to update app state")?;
+    }
+
+    terminal
+        .draw(|f| app.render(f))
+        .wrap_err("failed to render frame")?;
+    Ok(())
+}
+
+/// Writes frames without the terminal interface, until the source ends.
+///
+/// Frames are written to `--output`, or stdout. On a terminal, each frame is
+/// drawn over the previous one and fills the terminal unless `--size` is set.
+/// Otherwise, frames are separated by form feeds, so that they can be piped to
+/// other tools.
+fn run_headless(app: &mut App, source: Box<dyn FrameSource>, cli: &Cli) -> Result<()> {
+    if let Some(path) = cli.output.as_deref().filter(|path| *path != Path::new("-")) {
+        let file = File::create(path).wrap_err("failed to create output file")?;
+        return stream_frames(app, source, cli, &mut BufWriter::new(file), false);
+    }
+
+    let tty = io::stdout().is_terminal();
+    let mut stdout = BufWriter::new(io::stdout().lock());
+    let streamed = stream_frames(app, source, cli, &mut stdout, tty);
+    if tty {
+        // Leave the shell prompt below the last frame
+        let _ = writeln!(stdout).and_then(|()| stdout.flush());
+    }
+    streamed
+}
+
+/// Writes the frames of a source to an output, with the colors of the
+/// application.
+///
+/// Like the capture thread, this waits until each frame is due and skips
+/// occasional read errors.
+///
+/// # Arguments
+///
+/// * `tty` - Whether the output is a terminal, in which case each frame moves the
+///   cursor home and is drawn over the previous one, instead of being followed by
+///   a form feed. Other outputs get frames without colors unless `--color` is set
+fn stream_frames(
+    app: &mut App,
+    mut source: Box<dyn FrameSource>,
+    cli: &Cli,
+    output: &mut impl Write,
+    tty: bool,
+) -> Result<()> {
+    let min_frame_time = cli.fps.map(|fps| Duration::from_secs_f64(1.0 / fps));
+    let mut last_write: Option<Instant> = None;
+    let mut clock = PlaybackClock::default();
+    let mut reconnector = Reconnector::default();
+    if tty {
+        write!(output, "\x1b[2J").wrap_err("failed to clear the terminal")?;
+    }
+
+    loop {
+        let frame = match source.next_frame() {
+            Ok(Some(frame)) => frame,
+            Ok(None) => return Ok(()),
+            Err(err) if reconnector.record_failure() => return Err(err),
+            Err(_) => {
+                thread::sleep(IDLE_INTERVAL);
+                continue;
+            }
+        };
+        reconnector.record_success();
+
+        let due = clock.due(frame.timestamp, 1.0);
+        if let Some(wait) = due.checked_duration_since(Instant::now()) {
+            thread::sleep(wait);
+        }
+        if last_write
+            .zip(min_frame_time)
+            .is_some_and(|(written, min)| written.elapsed() < min)
+        {
+            continue;
+        }
+
+        // Follow the size of the terminal, which may change between frames
+        let terminal_size = tty
+            .then(|| crossterm::terminal::size().ok())
+            .flatten()
+            .filter(|&(width, height)| width > 0 && height > 0);
+        let (width, height) = cli.size.or(terminal_size).unwrap_or(HEADLESS_SIZE);
+        app.update(&frame.image, i32::from(width), i32::from(height))
+            .wrap_err("failed to update app state")?;
+        // Files and pipes get plain text, unless colors were asked for
+        let text = if tty || cli.color.is_some() {
+            app.ascii_frame.to_ansi(app.color_mode)
+        } else {
+            app.ascii_frame.to_string()
+        };
+        let written = if tty {
+            // Clear what a larger previous frame left below this one
+            write!(output, "\x1b[H{text}\x1b[J")
+        } else {
+            write!(output, "{text}\n\x0c")
+        };
+        match written.and_then(|()| output.flush()) {
+            Ok(()) => last_write = Some(Instant::now()),
+            // The reader, such as `head`, has stopped reading.
+            Err(err) if err.kind() == io::ErrorKind::BrokenPipe => return Ok(()),
+            Err(err) => return Err(err).wrap_err("failed to write frame"),
         }
     }
 }
+
+/// Writes a single frame to `--output`, or stdout, in the chosen format.
+///
+/// The frames before it are converted too, so that auto-exposure has settled by
+/// the time the snapshot is taken. Like headless mode, this skips occasional read
+/// errors.
+fn run_snapshot(app: &mut App, mut source: Box<dyn FrameSource>, cli: &Cli) -> Result<()> {
+    let (width, height) = cli.size.unwrap_or(HEADLESS_SIZE);
+    let mut reconnector = Reconnector::default();
+    let mut converted = 0;
+    while converted <= cli.warmup {
+        let frame = match source.next_frame() {
+            Ok(Some(frame)) => frame,
+            Ok(None) if converted > 0 => break,
+            Ok(None) => {
+                return Err(AppError::Source("the source ended before any frame".into()).into())
+            }
+            Err(err) if reconnector.record_failure() => return Err(err),
+            Err(_) => {
+                thread::sleep(IDLE_INTERVAL);
+                continue;
+            }
+        };
+        reconnector.record_success();
+        app.update(&frame.image, i32::from(width), i32::from(height))
+            .wrap_err("failed to update app state")?;
+        converted += 1;
+    }
+
+    let path = cli.output.as_deref().filter(|path| *path != Path::new("-"));
+    let format = cli
+        .format
+        .or_else(|| path.and_then(SnapshotFormat::from_path))
+        .unwrap_or(if app.color_mode == ColorMode::Mono {
+            SnapshotFormat::Text
+        } else {
+            SnapshotFormat::Ansi
+        });
+    let snapshot = format.render(&app.ascii_frame, app.color_mode);
+    match path {
+        Some(path) => std::fs::write(path, snapshot).wrap_err("failed to write snapshot"),
+        None => io::stdout()
+            .write_all(snapshot.as_bytes())
+            .wrap_err("failed to write snapshot"),
+    }
+}
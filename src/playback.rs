@@ -0,0 +1,193 @@
+//! # Playback Control
+//!
+//! This module holds the playback state of sources that are not live, such as
+//! video files: pause, single-frame steps, seeking, speed and looping. The
+//! state is changed from the UI and followed by the capture thread, which uses
+//! a `PlaybackClock` to show each frame at the right time.
+
+use std::fmt;
+use std::time::{Duration, Instant};
+
+/// The playback speeds cycled through, as multiples of the normal speed.
+pub const SPEEDS: [f64; 7] = [0.25, 0.5, 0.75, 1.0, 1.5, 2.0, 4.0];
+
+/// The playback state shared between the UI and the capture thread.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Playback {
+    /// Whether playback is paused.
+    pub paused: bool,
+    /// The playback speed, `1.0` being the normal speed.
+    pub speed: f64,
+    /// Whether playback restarts from the beginning at the end of the source.
+    pub looping: bool,
+    /// The timestamp of the last frame shown.
+    pub position: Duration,
+    /// The length of the source, if known.
+    pub duration: Option<Duration>,
+    /// Whether the end of the source was reached.
+    pub ended: bool,
+    seek: Option<Duration>,
+    step: bool,
+}
+
+impl Playback {
+    /// Creates the playback state of a source with the given length.
+    #[must_use]
+    pub fn new(duration: Option<Duration>) -> Self {
+        Playback {
+            paused: false,
+            speed: 1.0,
+            looping: false,
+            position: Duration::ZERO,
+            duration,
+            ended: false,
+            seek: None,
+            step: false,
+        }
+    }
+
+    /// Pauses or resumes playback.
+    pub fn toggle_pause(&mut self) {
+        self.paused = !self.paused;
+    }
+
+    /// Pauses playback and shows the next frame.
+    pub fn step(&mut self) {
+        self.paused = true;
+        self.step = true;
+    }
+
+    /// Toggles looping at the end of the source.
+    pub fn toggle_loop(&mut self) {
+        self.looping = !self.looping;
+    }
+
+    /// Switches to the next faster speed, if any.
+    pub fn faster(&mut self) {
+        if let Some(&speed) = SPEEDS.iter().find(|&&speed| speed > self.speed) {
+            self.speed = speed;
+        }
+    }
+
+    /// Switches to the next slower speed, if any.
+    pub fn slower(&mut self) {
+        if let Some(&speed) = SPEEDS.iter().rev().find(|&&speed| speed < self.speed) {
+            self.speed = speed;
+        }
+    }
+
+    /// Requests a seek relative to the current position, within the source.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use ascii_webcam::playback::Playback;
+    /// use std::time::Duration;
+    /// let mut playback = Playback::new(Some(Duration::from_secs(60)));
+    /// playback.seek_by(-5.0);
+    /// assert_eq!(playback.take_seek(), Some(Duration::ZERO));
+    /// playback.seek_by(90.0);
+    /// assert_eq!(playback.take_seek(), Some(Duration::from_secs(60)));
+    /// ```
+    pub fn seek_by(&mut self, seconds: f64) {
+        let from = self.seek.unwrap_or(self.position).as_secs_f64();
+        let mut target = (from + seconds).max(0.0);
+        if let Some(duration) = self.duration {
+            target = target.min(duration.as_secs_f64());
+        }
+        self.seek = Some(Duration::from_secs_f64(target));
+    }
+
+    /// Takes the pending seek request, if any.
+    pub fn take_seek(&mut self) -> Option<Duration> {
+        self.seek.take()
+    }
+
+    /// Takes the pending single-frame step request, if any.
+    pub fn take_step(&mut self) -> bool {
+        std::mem::take(&mut self.step)
+    }
+
+    /// Returns the fraction of the source already played, if its length is known.
+    #[must_use]
+    pub fn progress(&self) -> Option<f64> {
+        self.duration
+            .filter(|duration| !duration.is_zero())
+            .map(|duration| (self.position.as_secs_f64() / duration.as_secs_f64()).clamp(0.0, 1.0))
+    }
+}
+
+impl fmt::Display for Playback {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let state = if self.ended {
+            "■"
+        } else if self.paused {
+            "⏸"
+        } else {
+            "▶"
+        };
+        write!(f, "{state} {}", format_time(self.position))?;
+        if let Some(duration) = self.duration {
+            write!(f, " / {}", format_time(duration))?;
+        }
+        write!(f, " | {}x", self.speed)?;
+        if self.looping {
+            f.write_str(" | loop")?;
+        }
+        Ok(())
+    }
+}
+
+/// Formats a duration as `m:ss`, or `h:mm:ss` from one hour.
+///
+/// # Examples
+///
+/// ```
+/// use ascii_webcam::playback::format_time;
+/// use std::time::Duration;
+/// assert_eq!(format_time(Duration::from_secs(65)), "1:05");
+/// assert_eq!(format_time(Duration::from_secs(3725)), "1:02:05");
+/// ```
+#[must_use]
+pub fn format_time(duration: Duration) -> String {
+    let seconds = duration.as_secs();
+    let (hours, minutes, seconds) = (seconds / 3600, seconds / 60 % 60, seconds % 60);
+    if hours > 0 {
+        format!("{hours}:{minutes:02}:{seconds:02}")
+    } else {
+        format!("{minutes}:{seconds:02}")
+    }
+}
+
+/// Maps the timestamps of frames to the instants they are due, at a given speed.
+#[derive(Debug, Clone, Default)]
+pub struct PlaybackClock {
+    anchor: Option<(Instant, Duration, f64)>,
+}
+
+impl PlaybackClock {
+    /// Forgets the current timing, e.g. after a seek or a pause, so that the next
+    /// frame is due immediately.
+    pub fn reset(&mut self) {
+        self.anchor = None;
+    }
+
+    /// Returns the instant at which the frame with the given timestamp is due.
+    ///
+    /// Frames with a timestamp earlier than the previous one, as after looping,
+    /// restart the clock.
+    pub fn due(&mut self, timestamp: Duration, speed: f64) -> Instant {
+        let now = Instant::now();
+        match self.anchor {
+            Some((start, start_timestamp, anchor_speed))
+                if timestamp >= start_timestamp && (anchor_speed - speed).abs() < f64::EPSILON =>
+            {
+                start + (timestamp - start_timestamp).div_f64(speed.max(f64::EPSILON))
+            }
+            _ => {
+                self.anchor = Some((now, timestamp, speed));
+                now
+            }
+        }
+    }
+}
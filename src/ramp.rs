@@ -0,0 +1,236 @@
+//! # Character Ramps
+//!
+//! This module provides `CharRamp`, the ordered set of glyphs used to map
+//! luminance values to characters, along with a set of built-in presets.
+
+use crate::error::{AppError, Result};
+use std::fmt;
+
+/// The built-in character ramps.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum RampPreset {
+    /// The classic 10-glyph ramp ` .:-=+*#%@`.
+    #[default]
+    Classic,
+    /// Paul Bourke's 70-glyph ramp.
+    Bourke,
+    /// Unicode shade blocks ` ░▒▓█`.
+    Blocks,
+    /// The digits `0` to `9`.
+    Digits,
+    /// Only `0` and `1`.
+    Binary,
+}
+
+impl RampPreset {
+    /// All presets, in the order they are cycled through.
+    pub const ALL: [RampPreset; 5] = [
+        RampPreset::Classic,
+        RampPreset::Bourke,
+        RampPreset::Blocks,
+        RampPreset::Digits,
+        RampPreset::Binary,
+    ];
+
+    /// Returns the glyphs of the preset, ordered from darkest to brightest.
+    #[must_use]
+    pub fn glyphs(self) -> &'static str {
+        match self {
+            RampPreset::Classic => " .:-=+*#%@",
+            RampPreset::Bourke => {
+                " .'`^\",:;Il!i><~+_-?][}{1)(|\\/tfjrxnuvczXYUJCLQ0OZmwqpdbkhao*#MW&8%B@$"
+            }
+            RampPreset::Blocks => " ░▒▓█",
+            RampPreset::Digits => "0123456789",
+            RampPreset::Binary => "01",
+        }
+    }
+
+    /// Returns the preset following this one, wrapping around at the end.
+    #[must_use]
+    pub fn next(self) -> RampPreset {
+        let index = RampPreset::ALL
+            .iter()
+            .position(|&preset| preset == self)
+            .unwrap_or(0);
+        RampPreset::ALL[(index + 1) % RampPreset::ALL.len()]
+    }
+}
+
+impl fmt::Display for RampPreset {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            RampPreset::Classic => "classic",
+            RampPreset::Bourke => "bourke",
+            RampPreset::Blocks => "blocks",
+            RampPreset::Digits => "digits",
+            RampPreset::Binary => "binary",
+        };
+        f.write_str(name)
+    }
+}
+
+/// An ordered set of glyphs used to map luminance values to characters.
+///
+/// Glyphs are ordered from darkest (luminance `0`) to brightest (luminance `255`),
+/// which suits terminals with a dark background. Inverting the ramp reverses the
+/// mapping for terminals with a light background.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CharRamp {
+    glyphs: Vec<char>,
+    preset: Option<RampPreset>,
+    inverted: bool,
+}
+
+impl CharRamp {
+    /// Creates a new ramp from a user-supplied string of glyphs.
+    ///
+    /// Each Unicode scalar value of `glyphs` is one step of the ramp, so multi-byte
+    /// characters such as `░` are supported.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use ascii_webcam::ramp::CharRamp;
+    /// let ramp = CharRamp::new(" ·•●").unwrap();
+    /// assert_eq!(ramp.len(), 4);
+    /// assert_eq!(ramp.glyph(255), '●');
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// This function returns an `AppError::Config` error if `glyphs` contains fewer
+    /// than two characters.
+    pub fn new(glyphs: &str) -> Result<Self> {
+        let glyphs: Vec<char> = glyphs.chars().collect();
+        if glyphs.len() < 2 {
+            return Err(
+                AppError::Config("a character ramp needs at least two glyphs".to_string()).into(),
+            );
+        }
+        Ok(CharRamp {
+            glyphs,
+            preset: None,
+            inverted: false,
+        })
+    }
+
+    /// Creates a ramp from one of the built-in presets.
+    #[must_use]
+    pub fn preset(preset: RampPreset) -> Self {
+        CharRamp {
+            glyphs: preset.glyphs().chars().collect(),
+            preset: Some(preset),
+            inverted: false,
+        }
+    }
+
+    /// Returns the preset this ramp was created from, or `None` for custom ramps.
+    #[must_use]
+    pub fn preset_kind(&self) -> Option<RampPreset> {
+        self.preset
+    }
+
+    /// Returns the number of glyphs in the ramp.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.glyphs.len()
+    }
+
+    /// Returns `true` if the ramp has no glyphs.
+    ///
+    /// Ramps always hold at least two glyphs, so this is only provided for completeness.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.glyphs.is_empty()
+    }
+
+    /// Returns the glyphs of the ramp, ordered from darkest to brightest.
+    #[must_use]
+    pub fn glyphs(&self) -> &[char] {
+        &self.glyphs
+    }
+
+    /// Returns `true` if the ramp is inverted.
+    #[must_use]
+    pub fn is_inverted(&self) -> bool {
+        self.inverted
+    }
+
+    /// Toggles the inversion of the ramp.
+    pub fn invert(&mut self) {
+        self.inverted = !self.inverted;
+    }
+
+    /// Returns the ramp of the next built-in preset, keeping the inversion.
+    ///
+    /// Custom ramps are followed by the first preset.
+    #[must_use]
+    pub fn next_preset(&self) -> CharRamp {
+        let preset = self.preset.map_or(RampPreset::ALL[0], RampPreset::next);
+        CharRamp {
+            inverted: self.inverted,
+            ..CharRamp::preset(preset)
+        }
+    }
+
+    /// Maps a luminance value to a glyph of the ramp.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use ascii_webcam::ramp::{CharRamp, RampPreset};
+    /// let mut ramp = CharRamp::preset(RampPreset::Blocks);
+    /// assert_eq!(ramp.glyph(0), ' ');
+    /// assert_eq!(ramp.glyph(255), '█');
+    /// ramp.invert();
+    /// assert_eq!(ramp.glyph(0), '█');
+    /// ```
+    #[must_use]
+    pub fn glyph(&self, value: u8) -> char {
+        let value = if self.inverted { 255 - value } else { value };
+        let last = self.glyphs.len() - 1;
+        let index = (usize::from(value) * last) / 255;
+        self.glyphs[index.min(last)]
+    }
+
+    /// Returns the glyph of a quantized level, `0` being the darkest.
+    ///
+    /// Levels past the end of the ramp map to its brightest glyph.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use ascii_webcam::ramp::CharRamp;
+    /// let mut ramp = CharRamp::default();
+    /// assert_eq!(ramp.level_glyph(1), '.');
+    /// ramp.invert();
+    /// assert_eq!(ramp.level_glyph(1), '%');
+    /// ```
+    #[must_use]
+    pub fn level_glyph(&self, level: usize) -> char {
+        let last = self.glyphs.len() - 1;
+        let level = level.min(last);
+        let index = if self.inverted { last - level } else { level };
+        self.glyphs[index]
+    }
+}
+
+impl Default for CharRamp {
+    fn default() -> Self {
+        CharRamp::preset(RampPreset::default())
+    }
+}
+
+impl fmt::Display for CharRamp {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.preset {
+            Some(preset) => write!(f, "{preset}")?,
+            None => f.write_str("custom")?,
+        }
+        if self.inverted {
+            f.write_str(" (inverted)")?;
+        }
+        Ok(())
+    }
+}
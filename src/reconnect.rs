@@ -0,0 +1,165 @@
+//! # Reconnection
+//!
+//! This module decides when a frame source is lost and when to try opening it
+//! again. A source is considered lost after a few consecutive read failures,
+//! so that a single dropped frame does not interrupt the feed. Reconnection is
+//! then attempted with an exponential backoff, up to a maximum number of
+//! attempts, after which the error is reported to the user.
+
+use std::fmt;
+use std::time::Duration;
+
+/// The settings of the reconnection of lost sources.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ReconnectPolicy {
+    /// The number of consecutive read failures after which a source is lost.
+    pub failure_threshold: u32,
+    /// The number of reconnection attempts before giving up.
+    pub max_attempts: u32,
+    /// The delay after the first failed attempt, doubled after each further one.
+    pub initial_delay: Duration,
+    /// The longest delay between two attempts.
+    pub max_delay: Duration,
+}
+
+impl Default for ReconnectPolicy {
+    fn default() -> Self {
+        ReconnectPolicy {
+            failure_threshold: 5,
+            max_attempts: 10,
+            initial_delay: Duration::from_millis(500),
+            max_delay: Duration::from_secs(8),
+        }
+    }
+}
+
+/// The state of the connection to a source, as shown to the user.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Connection {
+    /// Frames are being received.
+    #[default]
+    Connected,
+    /// The source was lost and is being opened again.
+    Reconnecting {
+        /// The current attempt, starting at 1.
+        attempt: u32,
+        /// The number of attempts before giving up.
+        max_attempts: u32,
+    },
+}
+
+impl fmt::Display for Connection {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Connection::Connected => f.write_str("connected"),
+            Connection::Reconnecting {
+                attempt,
+                max_attempts,
+            } => write!(
+                f,
+                "camera lost — reconnecting (attempt {attempt}/{max_attempts})"
+            ),
+        }
+    }
+}
+
+/// Tracks the read failures of a source and the attempts to reconnect it.
+#[derive(Debug, Clone)]
+pub struct Reconnector {
+    policy: ReconnectPolicy,
+    failures: u32,
+    attempt: u32,
+}
+
+impl Reconnector {
+    /// Creates a reconnector following the given policy.
+    #[must_use]
+    pub fn new(policy: ReconnectPolicy) -> Self {
+        Reconnector {
+            policy,
+            failures: 0,
+            attempt: 0,
+        }
+    }
+
+    /// Records a successful read.
+    ///
+    /// # Returns
+    ///
+    /// Returns `true` if the source was lost, i.e. it has just been reconnected.
+    pub fn record_success(&mut self) -> bool {
+        let was_lost = self.is_lost();
+        self.failures = 0;
+        self.attempt = 0;
+        was_lost
+    }
+
+    /// Records a failed read.
+    ///
+    /// # Returns
+    ///
+    /// Returns `true` if the source is now considered lost.
+    pub fn record_failure(&mut self) -> bool {
+        self.failures = self.failures.saturating_add(1);
+        self.is_lost()
+    }
+
+    /// Returns `true` if the source has failed too many times in a row.
+    #[must_use]
+    pub fn is_lost(&self) -> bool {
+        self.failures >= self.policy.failure_threshold
+    }
+
+    /// Starts a new reconnection attempt.
+    ///
+    /// # Returns
+    ///
+    /// Returns the state to show during the attempt, or `None` once all attempts
+    /// have been used.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use ascii_webcam::reconnect::{ReconnectPolicy, Reconnector};
+    /// let policy = ReconnectPolicy {
+    ///     max_attempts: 2,
+    ///     ..ReconnectPolicy::default()
+    /// };
+    /// let mut reconnector = Reconnector::new(policy);
+    /// assert!(reconnector.start_attempt().is_some());
+    /// assert!(reconnector.start_attempt().is_some());
+    /// assert!(reconnector.start_attempt().is_none());
+    /// ```
+    pub fn start_attempt(&mut self) -> Option<Connection> {
+        if self.attempt >= self.policy.max_attempts {
+            return None;
+        }
+        self.attempt += 1;
+        Some(Connection::Reconnecting {
+            attempt: self.attempt,
+            max_attempts: self.policy.max_attempts,
+        })
+    }
+
+    /// Returns the number of attempts started since the source was lost.
+    #[must_use]
+    pub fn attempts(&self) -> u32 {
+        self.attempt
+    }
+
+    /// Returns how long to wait after the current attempt failed.
+    #[must_use]
+    pub fn delay(&self) -> Duration {
+        let doublings = self.attempt.saturating_sub(1).min(31);
+        self.policy
+            .initial_delay
+            .saturating_mul(1 << doublings)
+            .min(self.policy.max_delay)
+    }
+}
+
+impl Default for Reconnector {
+    fn default() -> Self {
+        Reconnector::new(ReconnectPolicy::default())
+    }
+}
@@ -0,0 +1,231 @@
+//! # Shape Matching
+//!
+//! This module picks, for each cell, the glyph whose shape best matches the
+//! image instead of only its brightness. Each glyph of an embedded 8x8 bitmap
+//! font is reduced to a 2x4 coverage mask, and each cell of the image is
+//! sampled at the same resolution. The best glyph for every quantized block of
+//! samples is computed once, so matching a cell is a single table lookup.
+
+use lazy_static::lazy_static;
+
+/// The number of samples per cell, horizontally.
+pub const SAMPLES_X: usize = 2;
+
+/// The number of samples per cell, vertically.
+pub const SAMPLES_Y: usize = 4;
+
+/// The number of samples per cell.
+const SAMPLES: usize = SAMPLES_X * SAMPLES_Y;
+
+/// The number of levels each sample is quantized to for the lookup table.
+const LEVELS: usize = 4;
+
+/// The number of font pixels covered by each sample (4 columns by 2 rows).
+const REGION_PIXELS: u8 = 8;
+
+/// The candidate glyphs with their 8x8 bitmaps, one byte per row from top to
+/// bottom, the most significant bit being the leftmost pixel.
+#[rustfmt::skip]
+const FONT: [(char, [u8; 8]); 56] = [
+    (' ', [0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00]),
+    ('.', [0x00, 0x00, 0x00, 0x00, 0x00, 0x18, 0x18, 0x00]),
+    (',', [0x00, 0x00, 0x00, 0x00, 0x00, 0x18, 0x18, 0x30]),
+    ('\'', [0x18, 0x18, 0x30, 0x00, 0x00, 0x00, 0x00, 0x00]),
+    ('`', [0x30, 0x18, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00]),
+    ('"', [0x6C, 0x6C, 0x48, 0x00, 0x00, 0x00, 0x00, 0x00]),
+    ('^', [0x10, 0x38, 0x6C, 0x00, 0x00, 0x00, 0x00, 0x00]),
+    ('-', [0x00, 0x00, 0x00, 0x7E, 0x00, 0x00, 0x00, 0x00]),
+    ('_', [0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0xFF]),
+    ('~', [0x00, 0x00, 0x73, 0xDC, 0x00, 0x00, 0x00, 0x00]),
+    ('=', [0x00, 0x00, 0x7E, 0x00, 0x00, 0x7E, 0x00, 0x00]),
+    ('+', [0x00, 0x18, 0x18, 0x7E, 0x18, 0x18, 0x00, 0x00]),
+    (':', [0x00, 0x18, 0x18, 0x00, 0x00, 0x18, 0x18, 0x00]),
+    (';', [0x00, 0x18, 0x18, 0x00, 0x00, 0x18, 0x18, 0x30]),
+    ('!', [0x18, 0x18, 0x18, 0x18, 0x18, 0x00, 0x18, 0x00]),
+    ('|', [0x18, 0x18, 0x18, 0x18, 0x18, 0x18, 0x18, 0x18]),
+    ('/', [0x03, 0x06, 0x0C, 0x18, 0x30, 0x60, 0xC0, 0x00]),
+    ('\\', [0xC0, 0x60, 0x30, 0x18, 0x0C, 0x06, 0x03, 0x00]),
+    ('(', [0x0C, 0x18, 0x30, 0x30, 0x30, 0x18, 0x0C, 0x00]),
+    (')', [0x30, 0x18, 0x0C, 0x0C, 0x0C, 0x18, 0x30, 0x00]),
+    ('[', [0x3C, 0x30, 0x30, 0x30, 0x30, 0x30, 0x3C, 0x00]),
+    (']', [0x3C, 0x0C, 0x0C, 0x0C, 0x0C, 0x0C, 0x3C, 0x00]),
+    ('<', [0x0C, 0x18, 0x30, 0x60, 0x30, 0x18, 0x0C, 0x00]),
+    ('>', [0x30, 0x18, 0x0C, 0x06, 0x0C, 0x18, 0x30, 0x00]),
+    ('*', [0x00, 0x66, 0x3C, 0xFF, 0x3C, 0x66, 0x00, 0x00]),
+    ('v', [0x00, 0x00, 0x66, 0x66, 0x66, 0x3C, 0x18, 0x00]),
+    ('o', [0x00, 0x00, 0x3C, 0x66, 0x66, 0x66, 0x3C, 0x00]),
+    ('x', [0x00, 0x00, 0x66, 0x3C, 0x18, 0x3C, 0x66, 0x00]),
+    ('n', [0x00, 0x00, 0x7C, 0x66, 0x66, 0x66, 0x66, 0x00]),
+    ('u', [0x00, 0x00, 0x66, 0x66, 0x66, 0x66, 0x3E, 0x00]),
+    ('T', [0x7E, 0x18, 0x18, 0x18, 0x18, 0x18, 0x18, 0x00]),
+    ('L', [0x60, 0x60, 0x60, 0x60, 0x60, 0x60, 0x7E, 0x00]),
+    ('J', [0x06, 0x06, 0x06, 0x06, 0x66, 0x66, 0x3C, 0x00]),
+    ('7', [0x7E, 0x06, 0x0C, 0x18, 0x18, 0x18, 0x18, 0x00]),
+    ('V', [0x66, 0x66, 0x66, 0x66, 0x66, 0x3C, 0x18, 0x00]),
+    ('Y', [0x66, 0x66, 0x66, 0x3C, 0x18, 0x18, 0x18, 0x00]),
+    ('U', [0x66, 0x66, 0x66, 0x66, 0x66, 0x66, 0x3C, 0x00]),
+    ('O', [0x3C, 0x66, 0x66, 0x66, 0x66, 0x66, 0x3C, 0x00]),
+    ('X', [0x66, 0x66, 0x3C, 0x18, 0x3C, 0x66, 0x66, 0x00]),
+    ('H', [0x66, 0x66, 0x66, 0x7E, 0x66, 0x66, 0x66, 0x00]),
+    ('A', [0x18, 0x3C, 0x66, 0x66, 0x7E, 0x66, 0x66, 0x00]),
+    ('P', [0x7C, 0x66, 0x66, 0x7C, 0x60, 0x60, 0x60, 0x00]),
+    ('d', [0x06, 0x06, 0x3E, 0x66, 0x66, 0x66, 0x3E, 0x00]),
+    ('b', [0x60, 0x60, 0x7C, 0x66, 0x66, 0x66, 0x7C, 0x00]),
+    ('q', [0x00, 0x00, 0x3E, 0x66, 0x66, 0x3E, 0x06, 0x06]),
+    ('p', [0x00, 0x00, 0x7C, 0x66, 0x66, 0x7C, 0x60, 0x60]),
+    ('#', [0x6C, 0x6C, 0xFE, 0x6C, 0xFE, 0x6C, 0x6C, 0x00]),
+    ('%', [0xC6, 0xCC, 0x0C, 0x18, 0x30, 0x66, 0xC6, 0x00]),
+    ('&', [0x38, 0x6C, 0x38, 0x76, 0xDC, 0xCC, 0x76, 0x00]),
+    ('8', [0x3C, 0x66, 0x66, 0x3C, 0x66, 0x66, 0x3C, 0x00]),
+    ('0', [0x3C, 0x66, 0x6E, 0x7E, 0x76, 0x66, 0x3C, 0x00]),
+    ('$', [0x18, 0x7E, 0xD8, 0x7C, 0x1B, 0xFC, 0x18, 0x00]),
+    ('M', [0xC6, 0xEE, 0xFE, 0xD6, 0xC6, 0xC6, 0xC6, 0x00]),
+    ('W', [0xC6, 0xC6, 0xD6, 0xFE, 0xEE, 0xC6, 0xC6, 0x00]),
+    ('B', [0xFC, 0x66, 0x66, 0x7C, 0x66, 0x66, 0xFC, 0x00]),
+    ('@', [0x7C, 0xC6, 0xDE, 0xDE, 0xDE, 0xC0, 0x78, 0x00]),
+];
+
+lazy_static! {
+    static ref LOOKUP: Vec<char> = build_lookup();
+}
+
+/// Returns the candidate glyphs, in order of preference when several match equally well.
+pub fn glyphs() -> impl Iterator<Item = char> {
+    FONT.iter().map(|&(glyph, _)| glyph)
+}
+
+/// Returns the coverage mask of a glyph of the embedded font.
+///
+/// # Returns
+///
+/// Returns, for each of the 2x4 samples of a cell row by row, the number of lit
+/// font pixels (out of 8) it covers, or `None` if the font has no such glyph.
+///
+/// # Examples
+///
+/// ```
+/// use ascii_webcam::shape::coverage;
+/// assert_eq!(coverage('_'), Some([0, 0, 0, 0, 0, 0, 4, 4]));
+/// assert_eq!(coverage('é'), None);
+/// ```
+#[must_use]
+pub fn coverage(glyph: char) -> Option<[u8; SAMPLES]> {
+    FONT.iter()
+        .find(|&&(candidate, _)| candidate == glyph)
+        .map(|(_, bitmap)| bitmap_coverage(bitmap))
+}
+
+/// Returns the glyph whose shape best matches a block of 2x4 luminance samples.
+///
+/// # Examples
+///
+/// ```
+/// use ascii_webcam::shape::match_block;
+/// assert_eq!(match_block(&[0; 8]), ' ');
+/// assert_eq!(match_block(&[0, 0, 0, 0, 0, 0, 255, 255]), '_');
+/// ```
+#[must_use]
+pub fn match_block(samples: &[u8; SAMPLES]) -> char {
+    let key = samples
+        .iter()
+        .enumerate()
+        .fold(0, |key, (i, &value)| key | (quantize(value) << (2 * i)));
+    LOOKUP[key]
+}
+
+/// Matches every cell of a grayscale image against the embedded font.
+///
+/// # Arguments
+///
+/// * `pixels` - The luminance samples, laid out row by row, of a `width * 2` x `height * 4` image
+/// * `width` - The number of cells per row
+/// * `height` - The number of rows of cells
+///
+/// # Returns
+///
+/// Returns the `width * height` best matching glyphs, row by row.
+///
+/// # Panics
+///
+/// Panics if `pixels` holds fewer than `width * 2 * height * 4` samples.
+#[must_use]
+pub fn encode(pixels: &[u8], width: usize, height: usize) -> Vec<char> {
+    let pixel_width = width * SAMPLES_X;
+    assert!(
+        pixels.len() >= pixel_width * height * SAMPLES_Y,
+        "image is smaller than the cell grid"
+    );
+
+    (0..height)
+        .flat_map(|cell_y| (0..width).map(move |cell_x| (cell_x, cell_y)))
+        .map(|(cell_x, cell_y)| {
+            let mut samples = [0; SAMPLES];
+            for (i, sample) in samples.iter_mut().enumerate() {
+                let x = cell_x * SAMPLES_X + i % SAMPLES_X;
+                let y = cell_y * SAMPLES_Y + i / SAMPLES_X;
+                *sample = pixels[y * pixel_width + x];
+            }
+            match_block(&samples)
+        })
+        .collect()
+}
+
+/// Quantizes a luminance value to one of the lookup levels.
+fn quantize(value: u8) -> usize {
+    usize::from(value) * LEVELS / 256
+}
+
+/// Counts the lit pixels of a bitmap covered by each sample.
+fn bitmap_coverage(bitmap: &[u8; 8]) -> [u8; SAMPLES] {
+    let mut coverage = [0; SAMPLES];
+    for (y, row) in bitmap.iter().enumerate() {
+        for x in 0..8 {
+            if row & (0x80 >> x) != 0 {
+                coverage[(y / 2) * SAMPLES_X + x / 4] += 1;
+            }
+        }
+    }
+    coverage
+}
+
+/// Finds the best glyph for every quantized block of samples.
+///
+/// Coverage is scaled so that the densest sample of the font stands for full
+/// brightness, and glyphs are compared by their squared error to the block.
+fn build_lookup() -> Vec<char> {
+    let masks: Vec<(char, [u8; SAMPLES])> = FONT
+        .iter()
+        .map(|(glyph, bitmap)| (*glyph, bitmap_coverage(bitmap)))
+        .collect();
+    let densest = masks
+        .iter()
+        .flat_map(|(_, mask)| mask.iter().copied())
+        .max()
+        .unwrap_or(REGION_PIXELS)
+        .max(1);
+
+    // Levels and coverage are compared on a common integer scale:
+    // `level / (LEVELS - 1)` against `coverage / densest`.
+    let top = u32::try_from(LEVELS - 1).unwrap_or(1);
+    let densest = u32::from(densest);
+
+    (0..LEVELS.pow(u32::try_from(SAMPLES).unwrap_or(0)))
+        .map(|key| {
+            let mut best = (' ', u32::MAX);
+            for (glyph, mask) in &masks {
+                let error: u32 = mask
+                    .iter()
+                    .enumerate()
+                    .map(|(i, &count)| {
+                        let level = u32::try_from((key >> (2 * i)) & (LEVELS - 1)).unwrap_or(0);
+                        (level * densest).abs_diff(u32::from(count) * top).pow(2)
+                    })
+                    .sum();
+                if error < best.1 {
+                    best = (*glyph, error);
+                }
+            }
+            best.0
+        })
+        .collect()
+}
@@ -0,0 +1,230 @@
+//! # Snapshots
+//!
+//! This module writes a single ASCII frame in a format meant to be kept: plain
+//! text, text with ANSI colors, an HTML page or an SVG image. HTML and SVG
+//! snapshots use the 24-bit colors of the cells in every color mode but `Mono`,
+//! and show light glyphs on a black background, like most terminals.
+
+use crate::color::{ColorMode, Rgb};
+use crate::frame::{AsciiFrame, Cell};
+use std::fmt::{self, Write};
+use std::path::Path;
+
+/// The width of a cell in SVG snapshots, in pixels.
+const SVG_CELL_WIDTH: usize = 8;
+/// The height of a cell in SVG snapshots, in pixels.
+const SVG_CELL_HEIGHT: usize = 16;
+/// The color of the background of HTML and SVG snapshots.
+const BACKGROUND: &str = "#000000";
+/// The color of glyphs without a color in HTML and SVG snapshots.
+const FOREGROUND: &str = "#cccccc";
+
+/// The file formats a snapshot can be written in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum SnapshotFormat {
+    /// The glyphs of the frame, without colors.
+    #[default]
+    Text,
+    /// The glyphs colored with ANSI escape sequences, for terminals.
+    Ansi,
+    /// A standalone HTML page with the frame in a `<pre>` element.
+    Html,
+    /// An SVG image with one text element per row.
+    Svg,
+}
+
+impl SnapshotFormat {
+    /// All snapshot formats.
+    pub const ALL: [SnapshotFormat; 4] = [
+        SnapshotFormat::Text,
+        SnapshotFormat::Ansi,
+        SnapshotFormat::Html,
+        SnapshotFormat::Svg,
+    ];
+
+    /// Returns the format matching the extension of a file, if any.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use ascii_webcam::snapshot::SnapshotFormat;
+    /// use std::path::Path;
+    /// assert_eq!(SnapshotFormat::from_path(Path::new("frame.SVG")), Some(SnapshotFormat::Svg));
+    /// assert_eq!(SnapshotFormat::from_path(Path::new("frame.ans")), Some(SnapshotFormat::Ansi));
+    /// assert_eq!(SnapshotFormat::from_path(Path::new("frame")), None);
+    /// ```
+    #[must_use]
+    pub fn from_path(path: &Path) -> Option<SnapshotFormat> {
+        let extension = path.extension()?.to_str()?.to_ascii_lowercase();
+        match extension.as_str() {
+            "txt" => Some(SnapshotFormat::Text),
+            "ans" | "ansi" => Some(SnapshotFormat::Ansi),
+            "html" | "htm" => Some(SnapshotFormat::Html),
+            "svg" => Some(SnapshotFormat::Svg),
+            _ => None,
+        }
+    }
+
+    /// Writes a frame in this format.
+    ///
+    /// # Arguments
+    ///
+    /// * `frame` - The frame to write
+    /// * `color_mode` - The colors to write the frame with
+    ///
+    /// # Returns
+    ///
+    /// Returns the contents of the snapshot, ending with a newline.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use ascii_webcam::color::ColorMode;
+    /// use ascii_webcam::frame::{AsciiFrame, Cell};
+    /// use ascii_webcam::snapshot::SnapshotFormat;
+    /// let frame = AsciiFrame::from_cells(2, 1, vec![Cell::new('<', 0), Cell::new('@', 255)]);
+    /// assert_eq!(SnapshotFormat::Text.render(&frame, ColorMode::Mono), "<@\n");
+    /// assert!(SnapshotFormat::Html.render(&frame, ColorMode::Mono).contains("&lt;@"));
+    /// ```
+    #[must_use]
+    pub fn render(self, frame: &AsciiFrame, color_mode: ColorMode) -> String {
+        match self {
+            SnapshotFormat::Text => format!("{frame}\n"),
+            SnapshotFormat::Ansi => format!("{}\n", frame.to_ansi(color_mode)),
+            SnapshotFormat::Html => render_html(frame, color_mode),
+            SnapshotFormat::Svg => render_svg(frame, color_mode),
+        }
+    }
+}
+
+impl fmt::Display for SnapshotFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            SnapshotFormat::Text => "text",
+            SnapshotFormat::Ansi => "ansi",
+            SnapshotFormat::Html => "html",
+            SnapshotFormat::Svg => "svg",
+        };
+        f.write_str(name)
+    }
+}
+
+/// Returns a color as a CSS hex color, such as `#ff8000`.
+fn hex(color: Rgb) -> String {
+    format!("#{:02x}{:02x}{:02x}", color.r, color.g, color.b)
+}
+
+/// Returns the foreground and background colors of a cell, unless colors are off.
+fn colors(cell: &Cell, color_mode: ColorMode) -> (Option<Rgb>, Option<Rgb>) {
+    if color_mode == ColorMode::Mono {
+        (None, None)
+    } else {
+        (cell.fg, cell.bg)
+    }
+}
+
+/// Appends a glyph to HTML or SVG text, escaping the characters XML reserves.
+fn push_escaped(text: &mut String, glyph: char) {
+    match glyph {
+        '&' => text.push_str("&amp;"),
+        '<' => text.push_str("&lt;"),
+        '>' => text.push_str("&gt;"),
+        '"' => text.push_str("&quot;"),
+        _ => text.push(glyph),
+    }
+}
+
+/// Writes a frame as a standalone HTML page.
+fn render_html(frame: &AsciiFrame, color_mode: ColorMode) -> String {
+    let mut body = String::new();
+    for (y, row) in frame.rows().enumerate() {
+        if y > 0 {
+            body.push('\n');
+        }
+        let mut current = (None, None);
+        for cell in row {
+            let cell_colors = colors(cell, color_mode);
+            if cell_colors != current {
+                if current != (None, None) {
+                    body.push_str("</span>");
+                }
+                let (fg, bg) = cell_colors;
+                if fg.is_some() || bg.is_some() {
+                    let fg = fg.map(|fg| format!("color:{};", hex(fg)));
+                    let bg = bg.map(|bg| format!("background:{};", hex(bg)));
+                    let style = fg.unwrap_or_default() + &bg.unwrap_or_default();
+                    let _ = write!(body, "<span style=\"{style}\">");
+                }
+                current = cell_colors;
+            }
+            push_escaped(&mut body, cell.glyph);
+        }
+        if current != (None, None) {
+            body.push_str("</span>");
+        }
+    }
+
+    format!(
+        "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n<title>ascii-webcam snapshot</title>\n</head>\n<body style=\"margin:0;background:{BACKGROUND}\">\n<pre style=\"margin:0;color:{FOREGROUND};font-family:monospace;line-height:1\">{body}</pre>\n</body>\n</html>\n"
+    )
+}
+
+/// Writes a frame as an SVG image, with a rectangle behind each cell that has a
+/// background color.
+fn render_svg(frame: &AsciiFrame, color_mode: ColorMode) -> String {
+    let width = frame.width() * SVG_CELL_WIDTH;
+    let height = frame.height() * SVG_CELL_HEIGHT;
+    let mut svg = format!(
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{width}\" height=\"{height}\" viewBox=\"0 0 {width} {height}\">\n<rect width=\"100%\" height=\"100%\" fill=\"{BACKGROUND}\"/>\n"
+    );
+
+    for (x, y, cell) in frame.indexed_cells() {
+        if let (_, Some(bg)) = colors(cell, color_mode) {
+            let _ = writeln!(
+                svg,
+                "<rect x=\"{}\" y=\"{}\" width=\"{SVG_CELL_WIDTH}\" height=\"{SVG_CELL_HEIGHT}\" fill=\"{}\"/>",
+                x * SVG_CELL_WIDTH,
+                y * SVG_CELL_HEIGHT,
+                hex(bg)
+            );
+        }
+    }
+
+    // Glyphs are stretched to the width of the cells, whatever the font.
+    let _ = writeln!(
+        svg,
+        "<g font-family=\"monospace\" font-size=\"{}\" fill=\"{FOREGROUND}\" xml:space=\"preserve\">",
+        SVG_CELL_HEIGHT - 2
+    );
+    for (y, row) in frame.rows().enumerate() {
+        let baseline = (y + 1) * SVG_CELL_HEIGHT - SVG_CELL_HEIGHT / 4;
+        let _ = write!(
+            svg,
+            "<text y=\"{baseline}\" textLength=\"{width}\" lengthAdjust=\"spacingAndGlyphs\">"
+        );
+        let mut run = String::new();
+        let mut run_fg = None;
+        for cell in row {
+            let (fg, _) = colors(cell, color_mode);
+            if fg != run_fg && !run.is_empty() {
+                push_tspan(&mut svg, &std::mem::take(&mut run), run_fg);
+            }
+            run_fg = fg;
+            push_escaped(&mut run, cell.glyph);
+        }
+        push_tspan(&mut svg, &run, run_fg);
+        svg.push_str("</text>\n");
+    }
+    svg.push_str("</g>\n</svg>\n");
+    svg
+}
+
+/// Appends a run of escaped glyphs sharing a foreground color to an SVG text element.
+fn push_tspan(svg: &mut String, run: &str, fg: Option<Rgb>) {
+    match fg {
+        Some(fg) => {
+            let _ = write!(svg, "<tspan fill=\"{}\">{run}</tspan>", hex(fg));
+        }
+        None => svg.push_str(run),
+    }
+}
@@ -0,0 +1,242 @@
+//! # Frame Sources
+//!
+//! This module defines the `FrameSource` trait implemented by everything the
+//! application can read frames from: cameras, video files, still images,
+//! image sequences and synthetic test patterns.
+
+use crate::error::{AppError, Result};
+use crate::image::{is_image, is_pattern, ImageSource};
+use crate::stream::{RawFormat, StreamSource};
+use crate::synthetic::{Pattern, SyntheticSource};
+use crate::video::VideoCapture;
+use color_eyre::eyre::WrapErr;
+use opencv::core::Mat;
+use std::fmt;
+use std::fs::File;
+use std::path::Path;
+use std::time::Duration;
+
+/// A frame read from a source, along with its presentation time.
+#[derive(Debug, Clone)]
+pub struct CapturedFrame {
+    /// The BGR image of the frame.
+    pub image: Mat,
+    /// The time at which the frame should be shown, relative to the start of the source.
+    pub timestamp: Duration,
+}
+
+/// The properties of a frame source.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct SourceProperties {
+    /// A short description of the source, such as a camera index or a file name.
+    pub name: String,
+    /// The width of the frames in pixels, or `0` if unknown.
+    pub width: i32,
+    /// The height of the frames in pixels, or `0` if unknown.
+    pub height: i32,
+    /// The number of frames per second, if known.
+    pub fps: Option<f64>,
+    /// The total number of frames, for sources that end.
+    pub frame_count: Option<u64>,
+    /// The pixel format delivered by the device or stream, such as a FOURCC code.
+    pub format: Option<String>,
+}
+
+impl SourceProperties {
+    /// Returns the length of the source, if both its frame count and rate are known.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use ascii_webcam::source::SourceProperties;
+    /// let properties = SourceProperties {
+    ///     fps: Some(25.0),
+    ///     frame_count: Some(250),
+    ///     ..SourceProperties::default()
+    /// };
+    /// assert_eq!(properties.duration().map(|d| d.as_secs()), Some(10));
+    /// ```
+    #[must_use]
+    pub fn duration(&self) -> Option<Duration> {
+        let fps = self.fps.filter(|fps| *fps > 0.0)?;
+        // Frame counts stay far below 2^52, so they are exact as `f64`.
+        #[allow(clippy::cast_precision_loss)]
+        let frames = self.frame_count? as f64;
+        Some(Duration::from_secs_f64(frames / fps))
+    }
+}
+
+impl fmt::Display for SourceProperties {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.name)?;
+        if self.width > 0 && self.height > 0 {
+            write!(f, " {}x{}", self.width, self.height)?;
+        }
+        if let Some(fps) = self.fps {
+            write!(f, " @ {fps:.0} fps")?;
+        }
+        if let Some(format) = &self.format {
+            write!(f, " {format}")?;
+        }
+        Ok(())
+    }
+}
+
+/// A source of video frames.
+///
+/// Sources are read from a dedicated capture thread, so they must be `Send`.
+pub trait FrameSource: Send {
+    /// Reads the next frame.
+    ///
+    /// # Returns
+    ///
+    /// Returns the next frame, or `None` once the end of the stream is reached.
+    ///
+    /// # Errors
+    ///
+    /// This function may return an error if the frame cannot be read.
+    fn next_frame(&mut self) -> Result<Option<CapturedFrame>>;
+
+    /// Returns the properties of the source.
+    fn properties(&self) -> SourceProperties;
+
+    /// Moves the source to the given position, so that the next frame is the one
+    /// shown at that time.
+    ///
+    /// # Returns
+    ///
+    /// Returns `true` if the source moved, or `false` if it cannot seek, as is
+    /// the case for cameras.
+    ///
+    /// # Errors
+    ///
+    /// This function may return an error if seeking fails.
+    fn seek(&mut self, position: Duration) -> Result<bool> {
+        let _ = position;
+        Ok(false)
+    }
+
+    /// Opens the source again after it stopped delivering frames, such as a
+    /// camera that was unplugged.
+    ///
+    /// # Returns
+    ///
+    /// Returns `true` if the source was opened again, or `false` if it cannot be
+    /// reopened, as is the case for files and pipes.
+    ///
+    /// # Errors
+    ///
+    /// This function may return an error if the source cannot be opened yet.
+    fn reconnect(&mut self) -> Result<bool> {
+        Ok(false)
+    }
+}
+
+impl<S: FrameSource + ?Sized> FrameSource for Box<S> {
+    fn next_frame(&mut self) -> Result<Option<CapturedFrame>> {
+        (**self).next_frame()
+    }
+
+    fn properties(&self) -> SourceProperties {
+        (**self).properties()
+    }
+
+    fn seek(&mut self, position: Duration) -> Result<bool> {
+        (**self).seek(position)
+    }
+
+    fn reconnect(&mut self) -> Result<bool> {
+        (**self).reconnect()
+    }
+}
+
+/// The prefix of the paths opening a synthetic test pattern, e.g. `synthetic:bars`.
+pub const SYNTHETIC_PREFIX: &str = "synthetic";
+
+/// The path reading a YUV4MPEG2 stream from stdin.
+pub const STDIN_PATH: &str = "-";
+
+/// The prefix of the paths reading raw BGR24 frames, e.g. `raw:640x480@30` for stdin
+/// or `raw:640x480@30:/tmp/frames` for a file or FIFO.
+pub const RAW_PREFIX: &str = "raw:";
+
+/// Opens the source matching a path:
+/// - a test pattern for `synthetic[:<pattern>]`
+/// - a YUV4MPEG2 stream for `-` (stdin), FIFOs and `.y4m` files
+/// - raw BGR24 frames for `raw:<width>x<height>[@<fps>][:<path>]`
+/// - a slideshow for images, directories and glob patterns
+/// - a video file otherwise
+///
+/// # Examples
+///
+/// ```
+/// use ascii_webcam::source::open_path;
+/// let source = open_path("synthetic:box".as_ref()).unwrap();
+/// assert_eq!(source.properties().name, "synthetic box");
+/// ```
+///
+/// # Errors
+///
+/// This function returns an error if the pattern is unknown, or if the images or
+/// the video cannot be opened.
+pub fn open_path(path: &Path) -> Result<Box<dyn FrameSource>> {
+    if let Some(spec) = path
+        .to_str()
+        .and_then(|path| path.strip_prefix(SYNTHETIC_PREFIX))
+        .filter(|spec| spec.is_empty() || spec.starts_with(':'))
+    {
+        let pattern = match spec.strip_prefix(':') {
+            Some(name) => Pattern::from_name(name)
+                .ok_or_else(|| AppError::Source(format!("unknown pattern {name}")))?,
+            None => Pattern::default(),
+        };
+        let source = SyntheticSource::new(640, 480, 30.0)
+            .with_pattern(pattern)
+            .with_counter(true);
+        Ok(Box::new(source))
+    } else if path.as_os_str() == STDIN_PATH {
+        Ok(Box::new(StreamSource::stdin(None)?))
+    } else if let Some(spec) = path.to_str().and_then(|path| path.strip_prefix(RAW_PREFIX)) {
+        let (format, file) = match spec.split_once(':') {
+            Some((format, file)) => (format, Some(file)),
+            None => (spec, None),
+        };
+        let format: RawFormat = format.parse()?;
+        match file {
+            Some(file) => {
+                let reader = File::open(file).wrap_err("failed to open raw stream")?;
+                Ok(Box::new(StreamSource::raw(reader, file, format)))
+            }
+            None => Ok(Box::new(StreamSource::stdin(Some(format))?)),
+        }
+    } else if is_fifo(path) || is_y4m(path) {
+        let reader = File::open(path).wrap_err("failed to open y4m stream")?;
+        Ok(Box::new(StreamSource::y4m(
+            reader,
+            &path.display().to_string(),
+        )?))
+    } else if is_pattern(path) || path.is_dir() || is_image(path) {
+        Ok(Box::new(ImageSource::open(path)?))
+    } else {
+        Ok(Box::new(VideoCapture::from_file(path)?))
+    }
+}
+
+/// Returns `true` if the path has the extension of a YUV4MPEG2 stream.
+fn is_y4m(path: &Path) -> bool {
+    path.extension()
+        .is_some_and(|extension| extension.eq_ignore_ascii_case("y4m"))
+}
+
+/// Returns `true` if the path is a named pipe.
+#[cfg(unix)]
+fn is_fifo(path: &Path) -> bool {
+    use std::os::unix::fs::FileTypeExt;
+    std::fs::metadata(path).is_ok_and(|metadata| metadata.file_type().is_fifo())
+}
+
+/// Returns `true` if the path is a named pipe.
+#[cfg(not(unix))]
+fn is_fifo(_path: &Path) -> bool {
+    false
+}
@@ -0,0 +1,415 @@
+//! # Piped Frames
+//!
+//! This module provides `StreamSource`, a frame source reading uncompressed
+//! frames from stdin, a FIFO or any other reader, so that the output of tools
+//! such as ffmpeg or gstreamer can be piped into the application:
+//!
+//! ```text
+//! ffmpeg -i video.mkv -f yuv4mpegpipe - | ascii-webcam -
+//! ffmpeg -i video.mkv -f rawvideo -pix_fmt bgr24 -s 640x480 - | ascii-webcam raw:640x480@30
+//! ```
+//!
+//! Two formats are supported: YUV4MPEG2 (y4m), whose header declares the size,
+//! rate and chroma subsampling of the frames, and raw BGR24 frames of a size
+//! given by the user. Keyboard input is unaffected, since the terminal reads it
+//! from the tty when stdin is not one.
+
+use crate::error::{AppError, Result};
+use crate::source::{CapturedFrame, FrameSource, SourceProperties};
+use color_eyre::eyre::WrapErr;
+use opencv::{
+    core::{self, Mat, Scalar},
+    prelude::*,
+};
+use std::fmt;
+use std::io::{self, BufRead, BufReader, ErrorKind, Read};
+use std::str::FromStr;
+use std::time::Duration;
+
+/// The signature starting every YUV4MPEG2 stream.
+const Y4M_MAGIC: &[u8] = b"YUV4MPEG2";
+
+/// The signature starting every YUV4MPEG2 frame.
+const Y4M_FRAME: &[u8] = b"FRAME";
+
+/// The longest stream or frame header accepted, to fail fast on other data.
+const MAX_HEADER_LENGTH: u64 = 1024;
+
+/// The largest width or height of a frame in pixels, to fail fast on sizes that
+/// would exhaust memory instead of allocating them.
+const MAX_FRAME_SIDE: usize = 8192;
+
+/// The rate assumed for raw frames without a declared rate.
+const DEFAULT_RAW_FPS: f64 = 30.0;
+
+/// The size and rate of raw BGR24 frames, written `<width>x<height>[@<fps>]`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RawFormat {
+    /// The width of the frames in pixels.
+    pub width: i32,
+    /// The height of the frames in pixels.
+    pub height: i32,
+    /// The number of frames per second.
+    pub fps: f64,
+}
+
+impl FromStr for RawFormat {
+    type Err = AppError;
+
+    /// Parses a raw format such as `640x480` or `1280x720@60`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use ascii_webcam::stream::RawFormat;
+    /// let format: RawFormat = "1280x720@60".parse().unwrap();
+    /// assert_eq!((format.width, format.height, format.fps), (1280, 720, 60.0));
+    /// assert!("1280".parse::<RawFormat>().is_err());
+    /// ```
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        let invalid = || AppError::Config(format!("invalid raw format {s}, expected WxH[@FPS]"));
+        let (size, fps) = match s.split_once('@') {
+            Some((size, fps)) => (size, fps.parse::<f64>().map_err(|_| invalid())?),
+            None => (s, DEFAULT_RAW_FPS),
+        };
+        let (width, height) = size.split_once('x').ok_or_else(invalid)?;
+        let format = RawFormat {
+            width: width.parse().map_err(|_| invalid())?,
+            height: height.parse().map_err(|_| invalid())?,
+            fps,
+        };
+        if format.width <= 0 || format.height <= 0 || !is_valid_fps(format.fps) {
+            return Err(invalid());
+        }
+        frame_pixels(format.width, format.height)?;
+        Ok(format)
+    }
+}
+
+impl fmt::Display for RawFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}x{}@{}", self.width, self.height, self.fps)
+    }
+}
+
+/// The chroma subsampling of YUV4MPEG2 frames.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Chroma {
+    /// Chroma at half the width and height, the default of the format.
+    C420,
+    /// Chroma at half the width.
+    C422,
+    /// Chroma at full resolution.
+    C444,
+    /// Luma only.
+    Mono,
+}
+
+impl Chroma {
+    /// Returns the chroma subsampling with the given name from a `C` header parameter.
+    fn from_name(name: &str) -> Option<Chroma> {
+        match name {
+            "420" | "420jpeg" | "420paldv" | "420mpeg2" => Some(Chroma::C420),
+            "422" => Some(Chroma::C422),
+            "444" => Some(Chroma::C444),
+            "mono" => Some(Chroma::Mono),
+            _ => None,
+        }
+    }
+
+    /// Returns the size of a chroma plane for frames of the given size.
+    fn plane_size(self, width: usize, height: usize) -> (usize, usize) {
+        match self {
+            Chroma::C420 => (width.div_ceil(2), height.div_ceil(2)),
+            Chroma::C422 => (width.div_ceil(2), height),
+            Chroma::C444 => (width, height),
+            Chroma::Mono => (0, 0),
+        }
+    }
+}
+
+/// The layout of the frames of a stream.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum StreamFormat {
+    Y4m(Chroma),
+    Raw,
+}
+
+/// A frame source reading uncompressed frames from a stream.
+pub struct StreamSource {
+    reader: Box<dyn BufRead + Send>,
+    name: String,
+    format: StreamFormat,
+    width: i32,
+    height: i32,
+    fps: f64,
+    buffer: Vec<u8>,
+    frame_index: u64,
+}
+
+impl StreamSource {
+    /// Creates a source reading a YUV4MPEG2 stream, starting with its header.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use ascii_webcam::source::FrameSource;
+    /// use ascii_webcam::stream::StreamSource;
+    /// let mut data = b"YUV4MPEG2 W2 H2 F25:1 Cmono\nFRAME\n".to_vec();
+    /// data.extend([16, 16, 235, 235]);
+    /// let mut source = StreamSource::y4m(std::io::Cursor::new(data), "pipe").unwrap();
+    /// assert_eq!(source.properties().fps, Some(25.0));
+    /// assert!(source.next_frame().unwrap().is_some());
+    /// assert!(source.next_frame().unwrap().is_none());
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// This function returns an `AppError::Source` error if the header cannot be read,
+    /// is not a YUV4MPEG2 header, or declares an unsupported size or chroma subsampling.
+    pub fn y4m(reader: impl Read + Send + 'static, name: &str) -> Result<Self> {
+        let mut reader = BufReader::new(reader);
+        let header = read_header(&mut reader)
+            .wrap_err("failed to read y4m header")?
+            .ok_or_else(|| AppError::Source("empty y4m stream".to_string()))?;
+        let parameters = header
+            .strip_prefix(Y4M_MAGIC)
+            .ok_or_else(|| AppError::Source("not a y4m stream".to_string()))?;
+
+        let (mut width, mut height, mut fps, mut chroma) = (0, 0, DEFAULT_RAW_FPS, Chroma::C420);
+        for parameter in String::from_utf8_lossy(parameters).split_ascii_whitespace() {
+            let mut chars = parameter.chars();
+            let tag = chars.next();
+            let value = chars.as_str();
+            match tag {
+                Some('W') => width = value.parse().unwrap_or(0),
+                Some('H') => height = value.parse().unwrap_or(0),
+                Some('F') => fps = parse_ratio(value).unwrap_or(0.0),
+                Some('C') => {
+                    chroma = Chroma::from_name(value).ok_or_else(|| {
+                        AppError::Source(format!("unsupported y4m chroma {value}"))
+                    })?;
+                }
+                // Interlacing, pixel aspect ratio and extensions do not change the layout.
+                _ => {}
+            }
+        }
+        if width <= 0 || height <= 0 || !is_valid_fps(fps) {
+            return Err(AppError::Source(format!(
+                "invalid y4m header {}",
+                String::from_utf8_lossy(&header)
+            ))
+            .into());
+        }
+        frame_pixels(width, height)?;
+
+        Ok(StreamSource::new(
+            Box::new(reader),
+            name,
+            StreamFormat::Y4m(chroma),
+            RawFormat { width, height, fps },
+        ))
+    }
+
+    /// Creates a source reading raw BGR24 frames of the given format.
+    #[must_use]
+    pub fn raw(reader: impl Read + Send + 'static, name: &str, format: RawFormat) -> Self {
+        StreamSource::new(
+            Box::new(BufReader::new(reader)),
+            name,
+            StreamFormat::Raw,
+            format,
+        )
+    }
+
+    /// Creates a source reading stdin: raw BGR24 frames if a format is given, or a
+    /// YUV4MPEG2 stream otherwise.
+    ///
+    /// # Errors
+    ///
+    /// This function returns an error if the YUV4MPEG2 header cannot be read.
+    pub fn stdin(raw: Option<RawFormat>) -> Result<Self> {
+        match raw {
+            Some(format) => Ok(StreamSource::raw(io::stdin(), "stdin", format)),
+            None => StreamSource::y4m(io::stdin(), "stdin"),
+        }
+    }
+
+    fn new(
+        reader: Box<dyn BufRead + Send>,
+        name: &str,
+        format: StreamFormat,
+        size: RawFormat,
+    ) -> Self {
+        StreamSource {
+            reader,
+            name: name.to_string(),
+            format,
+            width: size.width,
+            height: size.height,
+            fps: size.fps,
+            buffer: Vec::new(),
+            frame_index: 0,
+        }
+    }
+
+    /// Returns the number of bytes of a frame, without its header.
+    ///
+    /// # Errors
+    ///
+    /// This function returns an `AppError::Source` error if the frame is too large.
+    fn frame_size(&self) -> Result<usize> {
+        let pixels = frame_pixels(self.width, self.height)?;
+        let width = usize::try_from(self.width).unwrap_or(0);
+        let height = usize::try_from(self.height).unwrap_or(0);
+        Ok(match self.format {
+            StreamFormat::Y4m(chroma) => {
+                let (chroma_width, chroma_height) = chroma.plane_size(width, height);
+                pixels + 2 * chroma_width * chroma_height
+            }
+            StreamFormat::Raw => pixels * 3,
+        })
+    }
+
+    /// Converts the YUV planes in the buffer to BGR pixels, using BT.601 limited range.
+    fn convert_yuv(&self, chroma: Chroma, bgr: &mut [u8]) {
+        let width = usize::try_from(self.width).unwrap_or(0);
+        let height = usize::try_from(self.height).unwrap_or(0);
+        let (chroma_width, chroma_height) = chroma.plane_size(width, height);
+        let (luma, planes) = self.buffer.split_at(width * height);
+        let (u_plane, v_plane) = planes.split_at(chroma_width * chroma_height);
+        let (shift_x, shift_y) = match chroma {
+            Chroma::C420 => (1, 1),
+            Chroma::C422 => (1, 0),
+            Chroma::C444 | Chroma::Mono => (0, 0),
+        };
+
+        for (i, (pixel, &y)) in bgr.chunks_exact_mut(3).zip(luma).enumerate() {
+            let (u, v) = if chroma == Chroma::Mono {
+                (128, 128)
+            } else {
+                let index = ((i / width) >> shift_y) * chroma_width + ((i % width) >> shift_x);
+                (u_plane[index], v_plane[index])
+            };
+            let c = 298 * (i32::from(y) - 16);
+            let (d, e) = (i32::from(u) - 128, i32::from(v) - 128);
+            pixel[0] = clamp_u8((c + 516 * d + 128) >> 8);
+            pixel[1] = clamp_u8((c - 100 * d - 208 * e + 128) >> 8);
+            pixel[2] = clamp_u8((c + 409 * e + 128) >> 8);
+        }
+    }
+}
+
+impl FrameSource for StreamSource {
+    fn next_frame(&mut self) -> Result<Option<CapturedFrame>> {
+        if let StreamFormat::Y4m(_) = self.format {
+            let Some(header) = read_header(&mut self.reader).wrap_err("failed to read frame")?
+            else {
+                return Ok(None);
+            };
+            if !header.starts_with(Y4M_FRAME) {
+                return Err(AppError::Source("invalid y4m frame header".to_string()).into());
+            }
+        }
+
+        self.buffer.resize(self.frame_size()?, 0);
+        match self.reader.read_exact(&mut self.buffer) {
+            Ok(()) => {}
+            // A truncated last frame ends the stream, as when the writer is interrupted.
+            Err(err) if err.kind() == ErrorKind::UnexpectedEof => return Ok(None),
+            Err(err) => return Err(err).wrap_err("failed to read frame"),
+        }
+
+        let mut image = Mat::new_rows_cols_with_default(
+            self.height,
+            self.width,
+            core::CV_8UC3,
+            Scalar::all(0.0),
+        )
+        .wrap_err("failed to create frame")?;
+        let pixels = image.data_bytes_mut().wrap_err("failed to access pixels")?;
+        match self.format {
+            StreamFormat::Y4m(chroma) => self.convert_yuv(chroma, pixels),
+            StreamFormat::Raw => pixels.copy_from_slice(&self.buffer),
+        }
+
+        // Frame indices stay far below 2^52, so they are exact as `f64`.
+        #[allow(clippy::cast_precision_loss)]
+        let timestamp = Duration::from_secs_f64(self.frame_index as f64 / self.fps);
+        self.frame_index += 1;
+        Ok(Some(CapturedFrame { image, timestamp }))
+    }
+
+    fn properties(&self) -> SourceProperties {
+        SourceProperties {
+            name: self.name.clone(),
+            width: self.width,
+            height: self.height,
+            fps: Some(self.fps),
+            frame_count: None,
+            format: Some(
+                match self.format {
+                    StreamFormat::Y4m(_) => "YUV4MPEG2",
+                    StreamFormat::Raw => "BGR24",
+                }
+                .to_string(),
+            ),
+        }
+    }
+}
+
+/// Reads a header line without its newline, or `None` at the end of the stream.
+fn read_header(reader: &mut impl BufRead) -> io::Result<Option<Vec<u8>>> {
+    let mut line = Vec::new();
+    reader
+        .take(MAX_HEADER_LENGTH)
+        .read_until(b'\n', &mut line)?;
+    if line.is_empty() {
+        return Ok(None);
+    }
+    if line.pop() != Some(b'\n') {
+        return Err(io::Error::new(
+            ErrorKind::InvalidData,
+            "header too long or truncated",
+        ));
+    }
+    Ok(Some(line))
+}
+
+/// Parses a ratio such as `30000:1001`.
+fn parse_ratio(value: &str) -> Option<f64> {
+    let (numerator, denominator) = value.split_once(':')?;
+    let numerator = numerator.parse::<f64>().ok()?;
+    let denominator = denominator.parse::<f64>().ok()?;
+    (denominator > 0.0).then(|| numerator / denominator)
+}
+
+/// Returns the number of pixels of a frame of the given size.
+///
+/// # Errors
+///
+/// This function returns an `AppError::Source` error if the width or height is
+/// negative or larger than `MAX_FRAME_SIDE`.
+fn frame_pixels(width: i32, height: i32) -> std::result::Result<usize, AppError> {
+    usize::try_from(width)
+        .ok()
+        .zip(usize::try_from(height).ok())
+        .filter(|&(width, height)| width <= MAX_FRAME_SIDE && height <= MAX_FRAME_SIDE)
+        .map(|(width, height)| width * height)
+        .ok_or_else(|| {
+            AppError::Source(format!(
+                "frame size {width}x{height} exceeds the limit of \
+                 {MAX_FRAME_SIDE}x{MAX_FRAME_SIDE} pixels"
+            ))
+        })
+}
+
+/// Returns `true` if a frame rate is finite and positive.
+fn is_valid_fps(fps: f64) -> bool {
+    fps.is_finite() && fps > 0.0
+}
+
+/// Clamps a color component to the range of a byte.
+fn clamp_u8(value: i32) -> u8 {
+    u8::try_from(value.clamp(0, 255)).unwrap_or(u8::MAX)
+}
@@ -0,0 +1,274 @@
+//! # Synthetic Frames
+//!
+//! This module provides `SyntheticSource`, a frame source generating test
+//! patterns, so the application can run and be tested without a camera.
+//!
+//! Every pattern is a pure function of the frame index, so the frames are the
+//! same on every run, including the noise.
+
+use crate::error::Result;
+use crate::source::{CapturedFrame, FrameSource, SourceProperties};
+use color_eyre::eyre::WrapErr;
+use opencv::{
+    core::{self, Mat, Point, Scalar},
+    imgproc,
+    prelude::*,
+};
+use std::fmt;
+use std::time::Duration;
+
+/// The colors of the color bars in BGR order, at 75% intensity: white, yellow,
+/// cyan, green, magenta, red and blue.
+const COLOR_BARS: [[u8; 3]; 7] = [
+    [191, 191, 191],
+    [0, 191, 191],
+    [191, 191, 0],
+    [0, 191, 0],
+    [191, 0, 191],
+    [0, 0, 191],
+    [191, 0, 0],
+];
+
+/// The gray level behind the bouncing box.
+const BOX_BACKGROUND: u8 = 32;
+
+/// The test patterns a `SyntheticSource` can generate.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Pattern {
+    /// Vertical color bars.
+    ColorBars,
+    /// A horizontal gray gradient that scrolls over time.
+    #[default]
+    Gradient,
+    /// A white box bouncing off the edges of a dark frame.
+    BouncingBox,
+    /// Gray noise that changes every frame.
+    Noise,
+}
+
+impl Pattern {
+    /// All patterns, in the order they are cycled through.
+    pub const ALL: [Pattern; 4] = [
+        Pattern::ColorBars,
+        Pattern::Gradient,
+        Pattern::BouncingBox,
+        Pattern::Noise,
+    ];
+
+    /// Returns the pattern following this one, wrapping around at the end.
+    #[must_use]
+    pub fn next(self) -> Pattern {
+        let index = Pattern::ALL
+            .iter()
+            .position(|&pattern| pattern == self)
+            .unwrap_or(0);
+        Pattern::ALL[(index + 1) % Pattern::ALL.len()]
+    }
+
+    /// Returns the pattern with the given name, as displayed.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use ascii_webcam::synthetic::Pattern;
+    /// assert_eq!(Pattern::from_name("bars"), Some(Pattern::ColorBars));
+    /// assert_eq!(Pattern::from_name("stripes"), None);
+    /// ```
+    #[must_use]
+    pub fn from_name(name: &str) -> Option<Pattern> {
+        Pattern::ALL
+            .into_iter()
+            .find(|pattern| pattern.to_string() == name)
+    }
+}
+
+impl fmt::Display for Pattern {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Pattern::ColorBars => "bars",
+            Pattern::Gradient => "gradient",
+            Pattern::BouncingBox => "box",
+            Pattern::Noise => "noise",
+        };
+        f.write_str(name)
+    }
+}
+
+/// A frame source generating a test pattern, optionally with the frame index
+/// drawn in the top left corner.
+pub struct SyntheticSource {
+    width: i32,
+    height: i32,
+    fps: f64,
+    pattern: Pattern,
+    counter: bool,
+    frame_index: u64,
+}
+
+impl SyntheticSource {
+    /// Creates a source generating the default pattern, a scrolling gradient, at the
+    /// given size and rate.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use ascii_webcam::source::FrameSource;
+    /// use ascii_webcam::synthetic::SyntheticSource;
+    /// let mut source = SyntheticSource::new(320, 240, 30.0);
+    /// let frame = source.next_frame().unwrap().unwrap();
+    /// assert_eq!(frame.timestamp.as_secs(), 0);
+    /// ```
+    #[must_use]
+    pub fn new(width: i32, height: i32, fps: f64) -> Self {
+        SyntheticSource {
+            width: width.max(1),
+            height: height.max(1),
+            fps: if fps > 0.0 { fps } else { 30.0 },
+            pattern: Pattern::default(),
+            counter: false,
+            frame_index: 0,
+        }
+    }
+
+    /// Returns the source with the given pattern.
+    #[must_use]
+    pub fn with_pattern(mut self, pattern: Pattern) -> Self {
+        self.pattern = pattern;
+        self
+    }
+
+    /// Returns the source with the frame counter overlay enabled or disabled.
+    #[must_use]
+    pub fn with_counter(mut self, counter: bool) -> Self {
+        self.counter = counter;
+        self
+    }
+
+    /// Returns the generated pattern.
+    #[must_use]
+    pub fn pattern(&self) -> Pattern {
+        self.pattern
+    }
+
+    /// Fills the BGR pixels of a frame with the current pattern.
+    fn draw(&self, pixels: &mut [u8]) {
+        let width = usize::try_from(self.width).unwrap_or(1);
+        let height = usize::try_from(self.height).unwrap_or(1);
+        let index = usize::try_from(self.frame_index).unwrap_or(usize::MAX);
+
+        match self.pattern {
+            Pattern::ColorBars => {
+                for (i, pixel) in pixels.chunks_exact_mut(3).enumerate() {
+                    let bar = (i % width) * COLOR_BARS.len() / width;
+                    pixel.copy_from_slice(&COLOR_BARS[bar]);
+                }
+            }
+            Pattern::Gradient => {
+                // The gradient moves by four levels per frame and wraps around.
+                let offset = index % 64 * 4;
+                for (i, pixel) in pixels.chunks_exact_mut(3).enumerate() {
+                    let x = i % width;
+                    let value = u8::try_from((x * 256 / width + offset) % 256).unwrap_or(u8::MAX);
+                    pixel.fill(value);
+                }
+            }
+            Pattern::BouncingBox => {
+                let size = (width.min(height) / 4).max(1);
+                // The box moves by four pixels horizontally and three vertically per frame.
+                let left = bounce(index.wrapping_mul(4), width.saturating_sub(size));
+                let top = bounce(index.wrapping_mul(3), height.saturating_sub(size));
+                for (i, pixel) in pixels.chunks_exact_mut(3).enumerate() {
+                    let (x, y) = (i % width, i / width);
+                    let inside = (left..left + size).contains(&x) && (top..top + size).contains(&y);
+                    pixel.fill(if inside { u8::MAX } else { BOX_BACKGROUND });
+                }
+            }
+            Pattern::Noise => {
+                // A xorshift generator seeded by the frame index, never zero.
+                let mut seed = u32::try_from(self.frame_index % u64::from(u32::MAX))
+                    .unwrap_or_default()
+                    .wrapping_mul(0x9E37_79B9)
+                    | 1;
+                for pixel in pixels.chunks_exact_mut(3) {
+                    seed ^= seed << 13;
+                    seed ^= seed >> 17;
+                    seed ^= seed << 5;
+                    pixel.fill(seed.to_le_bytes()[0]);
+                }
+            }
+        }
+    }
+}
+
+/// Returns the position of an object moving back and forth between `0` and `range`,
+/// after it has travelled `distance`.
+fn bounce(distance: usize, range: usize) -> usize {
+    if range == 0 {
+        return 0;
+    }
+    let position = distance % (2 * range);
+    if position > range {
+        2 * range - position
+    } else {
+        position
+    }
+}
+
+impl FrameSource for SyntheticSource {
+    fn next_frame(&mut self) -> Result<Option<CapturedFrame>> {
+        let mut image = Mat::new_rows_cols_with_default(
+            self.height,
+            self.width,
+            core::CV_8UC3,
+            Scalar::all(0.0),
+        )
+        .wrap_err("failed to create frame")?;
+
+        let pixels = image.data_bytes_mut().wrap_err("failed to access pixels")?;
+        self.draw(pixels);
+
+        if self.counter {
+            let scale = f64::from(self.height) / 480.0;
+            // Outline the text in black so that it stays readable on any pattern.
+            for (color, thickness) in [(0.0, 4), (255.0, 2)] {
+                imgproc::put_text(
+                    &mut image,
+                    &self.frame_index.to_string(),
+                    Point::new(self.width / 40 + 1, self.height / 10 + 1),
+                    imgproc::FONT_HERSHEY_SIMPLEX,
+                    scale * 1.5,
+                    Scalar::all(color),
+                    thickness,
+                    imgproc::LINE_AA,
+                    false,
+                )
+                .wrap_err("failed to draw frame counter")?;
+            }
+        }
+
+        // Frame indices stay far below 2^52, so they are exact as `f64`.
+        #[allow(clippy::cast_precision_loss)]
+        let timestamp = Duration::from_secs_f64(self.frame_index as f64 / self.fps);
+        self.frame_index += 1;
+        Ok(Some(CapturedFrame { image, timestamp }))
+    }
+
+    fn properties(&self) -> SourceProperties {
+        SourceProperties {
+            name: format!("synthetic {}", self.pattern),
+            width: self.width,
+            height: self.height,
+            fps: Some(self.fps),
+            frame_count: None,
+            format: None,
+        }
+    }
+
+    fn seek(&mut self, position: Duration) -> Result<bool> {
+        // The position is rounded to a whole, non-negative frame index.
+        #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+        let frame_index = (position.as_secs_f64() * self.fps).round() as u64;
+        self.frame_index = frame_index;
+        Ok(true)
+    }
+}
@@ -1,20 +1,115 @@
 //! # Video Capture
 //!
 //! This module provides a wrapper around `OpenCV`'s `VideoCapture`
-//! for easy integration with the ASCII Webcam application.
+//! for easy integration with the ASCII Webcam application. It reads
+//! from cameras as well as video files.
+//!
+//! Cameras can be asked for a resolution, frame rate and pixel format with a
+//! `CaptureFormat`. Drivers settle on the closest mode they support, which
+//! `FrameSource::properties` reports.
 
 use crate::error::{AppError, Result};
+use crate::source::{CapturedFrame, FrameSource, SourceProperties};
 use color_eyre::eyre::WrapErr;
 use opencv::{
     core::Mat,
     prelude::*,
-    videoio::{VideoCapture as OpenCVVideoCapture, CAP_ANY},
+    videoio::{
+        VideoCapture as OpenCVVideoCapture, CAP_ANY, CAP_PROP_FOURCC, CAP_PROP_FPS,
+        CAP_PROP_FRAME_COUNT, CAP_PROP_FRAME_HEIGHT, CAP_PROP_FRAME_WIDTH, CAP_PROP_POS_MSEC,
+    },
 };
+use std::fmt;
+use std::path::Path;
+use std::time::{Duration, Instant};
+
+/// The capture mode requested from a camera. Unset fields keep the driver's choice.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct CaptureFormat {
+    /// The requested width of the frames in pixels.
+    pub width: Option<i32>,
+    /// The requested height of the frames in pixels.
+    pub height: Option<i32>,
+    /// The requested number of frames per second.
+    pub fps: Option<f64>,
+    /// The requested pixel format as a FOURCC code, such as `MJPG` or `YUYV`.
+    pub fourcc: Option<String>,
+}
+
+impl CaptureFormat {
+    /// Returns `true` if nothing is requested.
+    #[must_use]
+    pub fn is_default(&self) -> bool {
+        *self == CaptureFormat::default()
+    }
+}
+
+impl fmt::Display for CaptureFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut parts = Vec::new();
+        match (self.width, self.height) {
+            (Some(width), Some(height)) => parts.push(format!("{width}x{height}")),
+            (Some(width), None) => parts.push(format!("{width}x?")),
+            (None, Some(height)) => parts.push(format!("?x{height}")),
+            (None, None) => {}
+        }
+        if let Some(fps) = self.fps {
+            parts.push(format!("@ {fps:.0} fps"));
+        }
+        if let Some(fourcc) = &self.fourcc {
+            parts.push(fourcc.clone());
+        }
+        if parts.is_empty() {
+            f.write_str("default")
+        } else {
+            f.write_str(&parts.join(" "))
+        }
+    }
+}
+
+/// Packs a four-character code, such as `MJPG`, into the value of `CAP_PROP_FOURCC`.
+///
+/// # Examples
+///
+/// ```
+/// use ascii_webcam::video::{fourcc_code, fourcc_name};
+/// let code = fourcc_code("MJPG").unwrap();
+/// assert_eq!(fourcc_name(code).as_deref(), Some("MJPG"));
+/// assert!(fourcc_code("H264x").is_err());
+/// ```
+///
+/// # Errors
+///
+/// This function returns an `AppError::Config` error if the code is not made of
+/// exactly four ASCII characters.
+pub fn fourcc_code(fourcc: &str) -> Result<i32> {
+    match <[u8; 4]>::try_from(fourcc.as_bytes()) {
+        Ok(bytes) if fourcc.is_ascii() => Ok(i32::from_le_bytes(bytes)),
+        _ => Err(AppError::Config(format!("invalid FOURCC code {fourcc}")).into()),
+    }
+}
+
+/// Unpacks the value of `CAP_PROP_FOURCC` into a four-character code, if printable.
+#[must_use]
+pub fn fourcc_name(code: i32) -> Option<String> {
+    let bytes = code.to_le_bytes();
+    bytes
+        .iter()
+        .all(|byte| byte.is_ascii_graphic() || *byte == b' ')
+        .then(|| String::from_utf8_lossy(&bytes).trim_end().to_string())
+        .filter(|name| !name.is_empty())
+}
 
 /// A wrapper around `OpenCV`'s `VideoCapture`.
 #[allow(clippy::module_name_repetitions)]
 pub struct VideoCapture {
     capture: OpenCVVideoCapture,
+    name: String,
+    /// When a camera was opened. Frames of cameras are timestamped from this
+    /// instant, while video files carry their own timestamps.
+    started: Option<Instant>,
+    /// The index and requested mode of a camera, to reopen it once lost.
+    camera: Option<(i32, CaptureFormat)>,
 }
 
 impl VideoCapture {
@@ -39,7 +134,83 @@ impl VideoCapture {
     pub fn new(camera_index: i32) -> Result<Self> {
         let capture = OpenCVVideoCapture::new(camera_index, CAP_ANY)
             .wrap_err("failed to create VideoCapture")?;
-        Ok(VideoCapture { capture })
+        Ok(VideoCapture {
+            capture,
+            name: format!("camera {camera_index}"),
+            started: Some(Instant::now()),
+            camera: Some((camera_index, CaptureFormat::default())),
+        })
+    }
+
+    /// Opens a camera and asks it for a capture mode.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use ascii_webcam::video::{CaptureFormat, VideoCapture};
+    /// let format = CaptureFormat {
+    ///     width: Some(1280),
+    ///     height: Some(720),
+    ///     fourcc: Some("MJPG".to_string()),
+    ///     ..CaptureFormat::default()
+    /// };
+    /// let capture = VideoCapture::open(0, &format);
+    /// assert!(capture.is_ok());
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// This function returns an `AppError::Camera` error if the camera cannot be
+    /// opened, or another error if the capture mode cannot be requested.
+    pub fn open(camera_index: i32, format: &CaptureFormat) -> Result<Self> {
+        let mut capture = VideoCapture::new(camera_index)?;
+        if !capture.is_opened() {
+            return Err(AppError::Camera(format!("cannot open camera {camera_index}")).into());
+        }
+        capture
+            .configure(format)
+            .wrap_err("failed to configure camera")?;
+        capture.camera = Some((camera_index, format.clone()));
+        Ok(capture)
+    }
+
+    /// Opens a video file, such as an mp4, avi or mkv file.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - The path of the video file
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use ascii_webcam::video::VideoCapture;
+    /// let capture = VideoCapture::from_file("video.mp4".as_ref());
+    /// assert!(capture.is_ok());
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// This function returns an `AppError::Source` error if the file does not exist
+    /// or cannot be decoded.
+    pub fn from_file(path: &Path) -> Result<Self> {
+        let capture = OpenCVVideoCapture::from_file(&path.to_string_lossy(), CAP_ANY)
+            .wrap_err("failed to create VideoCapture")?;
+        if !capture.is_opened()? {
+            return Err(
+                AppError::Source(format!("cannot open video file {}", path.display())).into(),
+            );
+        }
+
+        let name = path.file_name().map_or_else(
+            || path.display().to_string(),
+            |name| name.to_string_lossy().into_owned(),
+        );
+        Ok(VideoCapture {
+            capture,
+            name,
+            started: None,
+            camera: None,
+        })
     }
 
     /// Reads a frame from the video capture device.
@@ -63,4 +234,125 @@ impl VideoCapture {
             Ok(frame)
         }
     }
+
+    /// Asks the camera for a capture mode.
+    ///
+    /// The pixel format is set first, since it limits the available resolutions and
+    /// frame rates. Drivers silently fall back to the closest mode they support, so
+    /// `FrameSource::properties` should be used to read what was negotiated.
+    ///
+    /// # Errors
+    ///
+    /// This function may return an error if the FOURCC code is invalid or the
+    /// backend rejects a property.
+    pub fn configure(&mut self, format: &CaptureFormat) -> Result<()> {
+        if let Some(fourcc) = &format.fourcc {
+            self.capture
+                .set(CAP_PROP_FOURCC, f64::from(fourcc_code(fourcc)?))
+                .wrap_err("failed to set pixel format")?;
+        }
+        if let Some(width) = format.width {
+            self.capture
+                .set(CAP_PROP_FRAME_WIDTH, f64::from(width))
+                .wrap_err("failed to set width")?;
+        }
+        if let Some(height) = format.height {
+            self.capture
+                .set(CAP_PROP_FRAME_HEIGHT, f64::from(height))
+                .wrap_err("failed to set height")?;
+        }
+        if let Some(fps) = format.fps {
+            self.capture
+                .set(CAP_PROP_FPS, fps)
+                .wrap_err("failed to set frame rate")?;
+        }
+        Ok(())
+    }
+
+    /// Returns `true` if the camera or file was opened successfully.
+    #[must_use]
+    pub fn is_opened(&self) -> bool {
+        self.capture.is_opened().unwrap_or(false)
+    }
+
+    /// Returns a property of the capture, or `None` if the backend does not report it.
+    fn property(&self, property: i32) -> Option<f64> {
+        self.capture
+            .get(property)
+            .ok()
+            .filter(|value| value.is_finite() && *value > 0.0)
+    }
+}
+
+impl FrameSource for VideoCapture {
+    fn next_frame(&mut self) -> Result<Option<CapturedFrame>> {
+        if let Some(started) = self.started {
+            let image = self.read_frame()?;
+            return Ok(Some(CapturedFrame {
+                image,
+                timestamp: started.elapsed(),
+            }));
+        }
+
+        let mut image = Mat::default();
+        let read = self
+            .capture
+            .read(&mut image)
+            .wrap_err("failed to read frame")?;
+        if !read || image.empty() {
+            return Ok(None);
+        }
+
+        let position = self.property(CAP_PROP_POS_MSEC).unwrap_or(0.0);
+        Ok(Some(CapturedFrame {
+            image,
+            timestamp: Duration::from_secs_f64(position / 1000.0),
+        }))
+    }
+
+    fn properties(&self) -> SourceProperties {
+        // Frame sizes and counts are reported as whole numbers.
+        #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+        let whole = |property| self.property(property).map(|value| value.round() as u64);
+        let size = |property| whole(property).and_then(|value| i32::try_from(value).ok());
+
+        SourceProperties {
+            name: self.name.clone(),
+            width: size(CAP_PROP_FRAME_WIDTH).unwrap_or(0),
+            height: size(CAP_PROP_FRAME_HEIGHT).unwrap_or(0),
+            fps: self.property(CAP_PROP_FPS),
+            frame_count: if self.started.is_some() {
+                None
+            } else {
+                whole(CAP_PROP_FRAME_COUNT)
+            },
+            // The code is stored in the low 32 bits of the property.
+            format: whole(CAP_PROP_FOURCC)
+                .and_then(|code| i32::try_from(code & u64::from(u32::MAX)).ok())
+                .and_then(fourcc_name),
+        }
+    }
+
+    fn seek(&mut self, position: Duration) -> Result<bool> {
+        if self.started.is_some() {
+            return Ok(false);
+        }
+        self.capture
+            .set(CAP_PROP_POS_MSEC, position.as_secs_f64() * 1000.0)
+            .wrap_err("failed to seek")
+    }
+
+    fn reconnect(&mut self) -> Result<bool> {
+        let Some((index, format)) = self.camera.clone() else {
+            return Ok(false);
+        };
+        // The device must be released before it can be opened again.
+        self.capture
+            .release()
+            .wrap_err("failed to release camera")?;
+        let reopened = VideoCapture::open(index, &format)?;
+        // Timestamps keep counting from when the camera was first opened.
+        self.capture = reopened.capture;
+        Ok(true)
+    }
 }
@@ -0,0 +1,59 @@
+use ascii_webcam::adjust::{apply, Adjustments, Equalization};
+use opencv::{core, prelude::*};
+
+#[test]
+fn test_lookup_table() {
+    let neutral = Adjustments::default();
+    assert!(neutral.is_neutral());
+    let table = neutral.lookup_table();
+    assert!((0u8..=255).all(|value| table[usize::from(value)] == value));
+
+    let contrast = Adjustments {
+        contrast: 2.0,
+        ..Adjustments::default()
+    };
+    let table = contrast.lookup_table();
+    assert_eq!(table[128], 128);
+    assert_eq!(table[64], 0);
+    assert_eq!(table[192], 255);
+
+    let gamma = Adjustments {
+        gamma: 2.0,
+        ..Adjustments::default()
+    };
+    let table = gamma.lookup_table();
+    assert!(table[64] > 64);
+    assert_eq!(table[0], 0);
+    assert_eq!(table[255], 255);
+}
+
+#[test]
+fn test_adjustment_steps() {
+    let mut adjustments = Adjustments::default();
+    adjustments.adjust_brightness(300);
+    assert_eq!(adjustments.brightness, 255);
+    for _ in 0..50 {
+        adjustments.adjust_gamma(-Adjustments::FACTOR_STEP);
+    }
+    assert!((adjustments.gamma - 0.1).abs() < f64::EPSILON);
+    adjustments.adjust_contrast(Adjustments::FACTOR_STEP * 3.0);
+    assert!((adjustments.contrast - 1.3).abs() < f64::EPSILON);
+    assert_eq!(Equalization::Clahe.next(), Equalization::Off);
+}
+
+#[test]
+fn test_apply() {
+    let gray = core::Mat::new_rows_cols_with_default(4, 4, core::CV_8UC1, core::Scalar::all(100.0))
+        .unwrap();
+    let adjustments = Adjustments {
+        brightness: 20,
+        equalization: Equalization::Clahe,
+        ..Adjustments::default()
+    };
+    let adjusted = apply(&gray, &adjustments).unwrap().unwrap();
+    assert_eq!(adjusted.rows(), 4);
+    assert_eq!(adjusted.cols(), 4);
+
+    // Neutral adjustments leave the image as it is, without copying it
+    assert!(apply(&gray, &Adjustments::default()).unwrap().is_none());
+}
@@ -1,10 +1,12 @@
 use ascii_webcam::app::App;
+use ascii_webcam::color::ColorMode;
 use opencv::{core, imgproc};
+use ratatui::layout::Rect;
 
 #[test]
 fn test_app_creation() {
     let app = App::new();
-    assert_eq!(app.ascii_frame, "");
+    assert!(app.ascii_frame.is_empty());
     assert_eq!(app.fps, 0.0);
     assert!(!app.show_help);
 }
@@ -31,6 +33,23 @@ fn test_app_update() {
     let result = app.update(&frame, 80, 24);
     assert!(result.is_ok(), "App update failed: {:?}", result.err());
     assert!(!app.ascii_frame.is_empty());
+    assert_eq!(app.ascii_frame.width(), 80);
+    assert_eq!(app.ascii_frame.height(), 24);
+}
+
+#[test]
+fn test_app_update_without_room() {
+    let mut app = App::new();
+    let frame =
+        core::Mat::new_rows_cols_with_default(480, 640, core::CV_8UC3, core::Scalar::all(255.0))
+            .unwrap();
+    app.update(&frame, 80, 24).unwrap();
+
+    // A terminal too small for the frame leaves it empty instead of failing
+    for (width, height) in [(0, 0), (80, 0), (0, 24)] {
+        app.update(&frame, width, height).unwrap();
+        assert!(app.ascii_frame.is_empty(), "{width}x{height}");
+    }
 }
 
 #[test]
@@ -42,3 +61,38 @@ fn test_app_toggle_help() {
     app.toggle_help();
     assert!(!app.show_help);
 }
+
+#[test]
+fn test_app_toggle_color() {
+    let mut app = App::new();
+    assert_eq!(app.color_mode, ColorMode::Mono);
+    app.toggle_color();
+    assert_ne!(app.color_mode, ColorMode::Mono);
+    app.toggle_color();
+    assert_eq!(app.color_mode, ColorMode::Mono);
+}
+
+#[test]
+fn test_app_block_mode_enables_color() {
+    let mut app = App::new();
+    app.next_render_mode();
+    assert_eq!(app.color_mode, ColorMode::Mono);
+    app.next_render_mode();
+    assert!(app.settings.mode.needs_color());
+    assert_ne!(app.color_mode, ColorMode::Mono);
+}
+
+#[test]
+fn test_app_frame_area() {
+    let mut app = App::new();
+    let area = app.frame_area(Rect::new(0, 0, 120, 40));
+    assert!(area.width < 120 && area.height < 40);
+
+    let frame =
+        core::Mat::new_rows_cols_with_default(480, 640, core::CV_8UC3, core::Scalar::all(0.0))
+            .unwrap();
+    app.update(&frame, i32::from(area.width), i32::from(area.height))
+        .unwrap();
+    assert_eq!(app.ascii_frame.width(), usize::from(area.width));
+    assert_eq!(app.ascii_frame.height(), usize::from(area.height));
+}
@@ -1,5 +1,7 @@
 // tests/ascii_tests.rs
-use ascii_webcam::ascii::{get_ascii_char, process_frame};
+use ascii_webcam::ascii::{get_ascii_char, process_frame, RenderMode, RenderSettings};
+use ascii_webcam::color::Rgb;
+use ascii_webcam::edges::EdgeMode;
 use opencv::{core, imgproc};
 
 #[test]
@@ -19,12 +21,16 @@ fn test_process_frame() {
         0,
     );
 
-    let result = process_frame(&frame, 80, 24);
+    let result = process_frame(&frame, 80, 24, &RenderSettings::default());
     assert!(result.is_ok(), "Process frame failed: {:?}", result.err());
     let ascii_frame = result.unwrap();
     assert!(!ascii_frame.is_empty());
+    assert_eq!(ascii_frame.width(), 80);
+    assert_eq!(ascii_frame.height(), 24);
 
     // Additional checks
+    let ascii_frame = ascii_frame.to_string();
+    assert_eq!(ascii_frame.lines().count(), 24);
     assert!(
         ascii_frame.contains(' '),
         "ASCII frame should contain spaces for white areas"
@@ -40,3 +46,131 @@ fn test_get_ascii_char() {
     assert_eq!(get_ascii_char(0), ' ');
 }
 
+#[test]
+fn test_process_frame_colors() {
+    // Left half blue, right half red (BGR)
+    let mut frame =
+        core::Mat::new_rows_cols_with_default(480, 640, core::CV_8UC3, core::Scalar::all(0.0))
+            .unwrap();
+    let _ = imgproc::rectangle(
+        &mut frame,
+        core::Rect::new(0, 0, 320, 480),
+        core::Scalar::new(255.0, 0.0, 0.0, 0.0),
+        -1,
+        imgproc::LINE_8,
+        0,
+    );
+    let _ = imgproc::rectangle(
+        &mut frame,
+        core::Rect::new(320, 0, 320, 480),
+        core::Scalar::new(0.0, 0.0, 255.0, 0.0),
+        -1,
+        imgproc::LINE_8,
+        0,
+    );
+
+    let ascii_frame = process_frame(&frame, 8, 4, &RenderSettings::default()).unwrap();
+    assert_eq!(ascii_frame.rows().count(), 4);
+    assert!(ascii_frame.rows().all(|row| row.len() == 8));
+
+    let left = ascii_frame.get(0, 0).unwrap();
+    let right = ascii_frame.get(7, 0).unwrap();
+    assert_eq!(left.fg, Some(Rgb::new(0, 0, 255)));
+    assert_eq!(right.fg, Some(Rgb::new(255, 0, 0)));
+    assert_eq!(right.glyph, get_ascii_char(right.luminance));
+}
+
+#[test]
+fn test_process_frame_braille() {
+    // Left half black, right half white
+    let mut frame =
+        core::Mat::new_rows_cols_with_default(480, 640, core::CV_8UC3, core::Scalar::all(0.0))
+            .unwrap();
+    let _ = imgproc::rectangle(
+        &mut frame,
+        core::Rect::new(320, 0, 320, 480),
+        core::Scalar::all(255.0),
+        -1,
+        imgproc::LINE_8,
+        0,
+    );
+
+    let settings = RenderSettings {
+        mode: RenderMode::Braille,
+        ..RenderSettings::default()
+    };
+    let ascii_frame = process_frame(&frame, 8, 4, &settings).unwrap();
+    assert_eq!(ascii_frame.width(), 8);
+    assert_eq!(ascii_frame.height(), 4);
+    assert_eq!(ascii_frame.get(0, 0).unwrap().glyph, '⠀');
+    assert_eq!(ascii_frame.get(7, 3).unwrap().glyph, '⣿');
+}
+
+#[test]
+fn test_process_frame_half_block() {
+    // Top half blue, bottom half red (BGR)
+    let mut frame =
+        core::Mat::new_rows_cols_with_default(480, 640, core::CV_8UC3, core::Scalar::all(0.0))
+            .unwrap();
+    let _ = imgproc::rectangle(
+        &mut frame,
+        core::Rect::new(0, 0, 640, 240),
+        core::Scalar::new(255.0, 0.0, 0.0, 0.0),
+        -1,
+        imgproc::LINE_8,
+        0,
+    );
+    let _ = imgproc::rectangle(
+        &mut frame,
+        core::Rect::new(0, 240, 640, 240),
+        core::Scalar::new(0.0, 0.0, 255.0, 0.0),
+        -1,
+        imgproc::LINE_8,
+        0,
+    );
+
+    let settings = RenderSettings {
+        mode: RenderMode::HalfBlock,
+        ..RenderSettings::default()
+    };
+    let ascii_frame = process_frame(&frame, 4, 1, &settings).unwrap();
+    let cell = ascii_frame.get(0, 0).unwrap();
+    assert_eq!(cell.glyph, '▀');
+    assert_eq!(cell.fg, Some(Rgb::new(0, 0, 255)));
+    assert_eq!(cell.bg, Some(Rgb::new(255, 0, 0)));
+
+    // The adjustments shift the colors by the change of luminance
+    let mut brighter = settings;
+    brighter.adjustments.brightness = 20;
+    let ascii_frame = process_frame(&frame, 4, 1, &brighter).unwrap();
+    let cell = ascii_frame.get(0, 0).unwrap();
+    assert_eq!(cell.fg, Some(Rgb::new(20, 20, 255)));
+    assert_eq!(cell.bg, Some(Rgb::new(255, 20, 20)));
+}
+
+#[test]
+fn test_process_frame_edges() {
+    // Left half black, right half white
+    let mut frame =
+        core::Mat::new_rows_cols_with_default(480, 640, core::CV_8UC3, core::Scalar::all(0.0))
+            .unwrap();
+    let _ = imgproc::rectangle(
+        &mut frame,
+        core::Rect::new(320, 0, 320, 480),
+        core::Scalar::all(255.0),
+        -1,
+        imgproc::LINE_8,
+        0,
+    );
+
+    let settings = RenderSettings {
+        edges: EdgeMode::Sobel,
+        ..RenderSettings::default()
+    };
+    let ascii_frame = process_frame(&frame, 16, 4, &settings).unwrap();
+    let row = ascii_frame.to_string();
+    let row = row.lines().nth(1).unwrap();
+    assert!(row.starts_with(' '));
+    assert!(row.ends_with('@'));
+    assert!(row.contains('|'));
+}
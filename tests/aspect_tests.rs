@@ -0,0 +1,29 @@
+use ascii_webcam::aspect::{place, FitMode};
+
+#[test]
+fn test_place_keeps_proportions() {
+    // A wide frame is limited by the width of the cells
+    let placement = place(1280, 720, 80, 40, 0.5, FitMode::Fit);
+    assert_eq!((placement.width, placement.height), (80, 23));
+    assert_eq!(placement.crop.width, 1280);
+
+    // A tall frame is limited by the height of the cells
+    let placement = place(480, 640, 80, 24, 0.5, FitMode::Letterbox);
+    assert_eq!((placement.width, placement.height), (36, 24));
+
+    // Square cells keep square pixels
+    let placement = place(100, 100, 50, 20, 1.0, FitMode::Fit);
+    assert_eq!((placement.width, placement.height), (20, 20));
+}
+
+#[test]
+fn test_place_fill_crops() {
+    let placement = place(640, 480, 80, 24, 0.5, FitMode::Fill);
+    assert_eq!((placement.width, placement.height), (80, 24));
+    // 80x24 cells of 1:2 show a 5:3 image, so the top and bottom are cropped
+    assert_eq!(placement.crop.width, 640);
+    assert_eq!(placement.crop.height, 384);
+    assert_eq!(placement.crop.y, 48);
+
+    assert_eq!(FitMode::Fill.next(), FitMode::Letterbox);
+}
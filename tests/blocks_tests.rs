@@ -0,0 +1,43 @@
+use ascii_webcam::blocks::{half_blocks, quadrants, sextant_char, sextants};
+use ascii_webcam::color::Rgb;
+
+const BLACK: Rgb = Rgb::new(0, 0, 0);
+const WHITE: Rgb = Rgb::new(255, 255, 255);
+const RED: Rgb = Rgb::new(255, 0, 0);
+
+#[test]
+fn test_half_blocks() {
+    let cells = half_blocks(&[RED, WHITE, BLACK, RED], 2, 1);
+    assert_eq!(cells.len(), 2);
+    assert_eq!(cells[0].glyph, '▀');
+    assert_eq!((cells[0].fg, cells[0].bg), (RED, BLACK));
+    assert_eq!((cells[1].fg, cells[1].bg), (WHITE, RED));
+}
+
+#[test]
+fn test_quadrants_best_split() {
+    // Top-left and bottom-right white, the others black.
+    let cells = quadrants(&[WHITE, BLACK, BLACK, WHITE], 1, 1);
+    assert_eq!(cells[0].glyph, '▚');
+    assert_eq!((cells[0].fg, cells[0].bg), (WHITE, BLACK));
+
+    // A uniform block is drawn as a full block.
+    let cells = quadrants(&[RED; 4], 1, 1);
+    assert_eq!(cells[0].glyph, '█');
+    assert_eq!(cells[0].fg, RED);
+}
+
+#[test]
+fn test_sextants() {
+    // Left column red, right column white.
+    let pixels = [RED, WHITE, RED, WHITE, RED, WHITE];
+    let cells = sextants(&pixels, 1, 1);
+    assert_eq!(cells[0].glyph, '▐');
+    assert_eq!((cells[0].fg, cells[0].bg), (WHITE, RED));
+
+    // Every mask except the four block elements maps to a distinct sextant.
+    let glyphs: std::collections::HashSet<char> = (0..64).map(sextant_char).collect();
+    assert_eq!(glyphs.len(), 64);
+    assert_eq!(sextant_char(0), ' ');
+    assert_eq!(sextant_char(63), '█');
+}
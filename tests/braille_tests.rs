@@ -0,0 +1,17 @@
+use ascii_webcam::braille::{braille_char, encode};
+
+#[test]
+fn test_braille_char() {
+    assert_eq!(braille_char(0x01), '⠁');
+    assert_eq!(braille_char(0x80), '⢀');
+}
+
+#[test]
+fn test_encode_dot_layout() {
+    // Two cells side by side: the first has only its top-left dot lit,
+    // the second only its bottom-right dot.
+    let mut lit = vec![false; 4 * 4];
+    lit[0] = true;
+    lit[3 * 4 + 3] = true;
+    assert_eq!(encode(&lit, 2, 1), vec!['⠁', '⢀']);
+}
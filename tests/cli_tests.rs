@@ -0,0 +1,112 @@
+use ascii_webcam::app::App;
+use ascii_webcam::ascii::RenderMode;
+use ascii_webcam::aspect::FitMode;
+use ascii_webcam::cli::Cli;
+use ascii_webcam::color::ColorMode;
+use ascii_webcam::ramp::RampPreset;
+use ascii_webcam::snapshot::SnapshotFormat;
+use clap::Parser;
+use clap_complete::Shell;
+
+fn parse(args: &[&str]) -> Result<Cli, clap::Error> {
+    Cli::try_parse_from(std::iter::once("ascii-webcam").chain(args.iter().copied()))
+}
+
+#[test]
+fn test_defaults() {
+    let cli = parse(&[]).unwrap();
+    assert_eq!(cli.camera, None);
+    assert!(cli.source.is_none());
+    assert!(cli.capture_format().unwrap().is_default());
+
+    let mut app = App::new();
+    cli.apply(&mut app).unwrap();
+    assert_eq!(app.settings.mode, RenderMode::Ramp);
+    assert_eq!(app.color_mode, ColorMode::Mono);
+    assert!(!app.mirror);
+}
+
+#[test]
+fn test_settings() {
+    let args = "-c 2 --resolution 1280x720 --capture-fps 60 --fourcc MJPG -m sextant -r blocks \
+                -i --color 256 --fit fill --fps 15 -s 100x40 -vv";
+    let cli = parse(&args.split_whitespace().collect::<Vec<_>>()).unwrap();
+    assert_eq!(cli.camera, Some(2));
+    assert_eq!(cli.fps, Some(15.0));
+    assert_eq!(cli.size, Some((100, 40)));
+    assert_eq!(cli.verbose, 2);
+    assert_eq!(
+        cli.capture_format().unwrap().to_string(),
+        "1280x720 @ 60 fps MJPG"
+    );
+
+    let mut app = App::new();
+    cli.apply(&mut app).unwrap();
+    assert_eq!(app.settings.mode, RenderMode::Sextant);
+    assert_eq!(app.settings.ramp.preset_kind(), Some(RampPreset::Blocks));
+    assert!(app.settings.ramp.is_inverted());
+    assert_eq!(app.color_mode, ColorMode::Ansi256);
+    assert_eq!(app.fit, FitMode::Fill);
+
+    let cli = parse(&[
+        "synthetic:bars",
+        "--glyphs",
+        " ·•●",
+        "--headless",
+        "-o",
+        "-",
+    ])
+    .unwrap();
+    assert_eq!(cli.render_settings().unwrap().ramp.len(), 4);
+
+    let cli = parse(&[
+        "--snapshot",
+        "--warmup",
+        "10",
+        "--format",
+        "svg",
+        "-o",
+        "a.svg",
+    ])
+    .unwrap();
+    assert!(cli.snapshot);
+    assert_eq!(cli.warmup, 10);
+    assert_eq!(cli.format, Some(SnapshotFormat::Svg));
+}
+
+#[test]
+fn test_invalid_arguments() {
+    for args in [
+        &["--size", "0x24"][..],
+        &["--size", "80"],
+        &["--fps", "-1"],
+        &["--renderer", "pixels"],
+        &["--ramp", "blocks", "--glyphs", "ab"],
+        &["--camera", "1", "video.mp4"],
+        &["--output", "frames.txt"],
+        &["--warmup", "5"],
+        &["--snapshot", "--headless"],
+        &["--snapshot", "--format", "png"],
+    ] {
+        assert!(parse(args).is_err(), "{args:?}");
+    }
+
+    // Values clap cannot check are rejected when the settings are built.
+    assert!(parse(&["--glyphs", "x"])
+        .unwrap()
+        .render_settings()
+        .is_err());
+    assert!(parse(&["--fourcc", "H264x"])
+        .unwrap()
+        .capture_format()
+        .is_err());
+}
+
+#[test]
+fn test_completions() {
+    let mut script = Vec::new();
+    Cli::write_completions(Shell::Bash, &mut script);
+    let script = String::from_utf8(script).unwrap();
+    assert!(script.contains("ascii-webcam"));
+    assert!(script.contains("--renderer"));
+}
@@ -0,0 +1,31 @@
+use ascii_webcam::color::{rgb_to_ansi16, rgb_to_ansi256, ColorMode, Rgb};
+use ratatui::style::Color;
+
+#[test]
+fn test_rgb_to_ansi256() {
+    assert_eq!(rgb_to_ansi256(Rgb::new(0, 0, 0)), 16);
+    assert_eq!(rgb_to_ansi256(Rgb::new(255, 255, 255)), 231);
+    assert_eq!(rgb_to_ansi256(Rgb::new(0, 0, 255)), 21);
+    assert_eq!(rgb_to_ansi256(Rgb::new(100, 100, 100)), 241);
+}
+
+#[test]
+fn test_rgb_to_ansi16() {
+    assert_eq!(rgb_to_ansi16(Rgb::new(10, 10, 10)), 0);
+    assert_eq!(rgb_to_ansi16(Rgb::new(0, 200, 0)), 2);
+    assert_eq!(rgb_to_ansi16(Rgb::new(250, 250, 250)), 15);
+}
+
+#[test]
+fn test_color_mode_to_color() {
+    let color = Rgb::new(12, 34, 56);
+    assert_eq!(ColorMode::Mono.to_color(color), None);
+    assert_eq!(
+        ColorMode::TrueColor.to_color(color),
+        Some(Color::Rgb(12, 34, 56))
+    );
+    assert!(matches!(
+        ColorMode::Ansi256.to_color(color),
+        Some(Color::Indexed(_))
+    ));
+}
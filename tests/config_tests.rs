@@ -0,0 +1,136 @@
+use ascii_webcam::app::App;
+use ascii_webcam::ascii::RenderMode;
+use ascii_webcam::cli::{Cli, ColorChoice};
+use ascii_webcam::config::{Config, ConfigWatcher};
+use ascii_webcam::ramp::RampPreset;
+use clap::Parser;
+use std::path::Path;
+use std::time::Duration;
+
+const CONFIG: &str = r#"
+source = "synthetic:bars"
+renderer = "braille"
+ramp = "blocks"
+color = "256"
+mirror = true
+fps = 12.5
+size = "100x30"
+
+[keys]
+quit = "x"
+
+[overlays]
+stats = false
+"#;
+
+#[test]
+fn test_merge_with_command_line() {
+    let config = Config::parse(CONFIG).unwrap();
+    assert_eq!(config.keys.get("quit").map(String::as_str), Some("x"));
+
+    let cli = Cli::try_parse_from(["ascii-webcam", "--renderer", "shape"]).unwrap();
+    let merged = config.merge(&cli).unwrap();
+    assert_eq!(merged.source.as_deref(), Some(Path::new("synthetic:bars")));
+    // The command line takes precedence over the file.
+    assert_eq!(merged.renderer, Some(RenderMode::Shape));
+    assert_eq!(merged.ramp, Some(RampPreset::Blocks));
+    assert_eq!(merged.color, Some(ColorChoice::Ansi256));
+    assert_eq!(merged.fps, Some(12.5));
+    assert_eq!(merged.size, Some((100, 30)));
+
+    let mut app = App::new();
+    merged.apply(&mut app).unwrap();
+    assert!(app.mirror);
+    assert!(!app.show_stats);
+    assert!(app.show_status);
+
+    // Negative flags undo the switches of the file.
+    let cli = Cli::try_parse_from(["ascii-webcam", "--no-mirror", "--stats"]).unwrap();
+    let mut app = App::new();
+    config.merge(&cli).unwrap().apply(&mut app).unwrap();
+    assert!(!app.mirror);
+    assert!(app.show_stats);
+
+    // A camera or a ramp on the command line replaces the source or ramp of the file.
+    let cli = Cli::try_parse_from(["ascii-webcam", "-c", "1", "--glyphs", " .o"]).unwrap();
+    let merged = config.merge(&cli).unwrap();
+    assert_eq!(merged.source, None);
+    assert_eq!(merged.camera, Some(1));
+    assert_eq!(merged.ramp, None);
+    assert_eq!(merged.render_settings().unwrap().ramp.len(), 3);
+}
+
+#[test]
+fn test_reload_keeps_key_changes() {
+    let cli = Cli::try_parse_from(["ascii-webcam"]).unwrap();
+    let previous = Config::parse(CONFIG).unwrap().merge(&cli).unwrap();
+    let mut app = App::new();
+    previous.apply(&mut app).unwrap();
+    app.next_ramp();
+    app.toggle_color();
+    let ramp = app.settings.ramp.to_string();
+    let color_mode = app.color_mode;
+
+    // Only the keys change, so the state chosen with keys stays.
+    let config = Config::parse(&CONFIG.replace("quit = \"x\"", "quit = \"z\"")).unwrap();
+    let settings = config.merge(&cli).unwrap();
+    settings.apply_changes(&previous, &mut app).unwrap();
+    assert_eq!(app.settings.ramp.to_string(), ramp);
+    assert_eq!(app.color_mode, color_mode);
+
+    // A changed setting replaces the state chosen with keys.
+    let config = Config::parse(&CONFIG.replace("ramp = \"blocks\"", "ramp = \"classic\"")).unwrap();
+    config
+        .merge(&cli)
+        .unwrap()
+        .apply_changes(&settings, &mut app)
+        .unwrap();
+    assert_eq!(app.settings.ramp.preset_kind(), Some(RampPreset::Classic));
+    assert_eq!(app.color_mode, color_mode);
+}
+
+#[test]
+fn test_invalid_settings() {
+    let cli = Cli::try_parse_from(["ascii-webcam"]).unwrap();
+    for contents in [
+        "renderer = \"pixels\"",
+        "size = \"80\"",
+        "fps = 0",
+        "color = \"purple\"",
+    ] {
+        let config = Config::parse(contents).unwrap();
+        assert!(config.merge(&cli).is_err(), "{contents}");
+    }
+
+    let err = Config::parse("mirror = true\nunknown = 1").unwrap_err();
+    assert!(err.to_string().contains("line 2"), "{err}");
+    assert!(Config::parse("[overlays]\nclock = true").is_err());
+}
+
+#[test]
+fn test_watcher_reports_changes() {
+    let dir = std::env::temp_dir().join(format!("ascii-webcam-config-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let path = dir.join("config.toml");
+    std::fs::write(&path, "renderer = \"ramp\"").unwrap();
+    assert_eq!(Config::locate(Some(&path)), Some(path.clone()));
+
+    let watcher = ConfigWatcher::new(&path).unwrap();
+    // Other files of the directory are ignored.
+    std::fs::write(dir.join("other.toml"), "").unwrap();
+    assert!(watcher
+        .changes()
+        .recv_timeout(Duration::from_millis(500))
+        .is_err());
+    std::fs::write(&path, "renderer = \"braille\"").unwrap();
+    assert!(watcher
+        .changes()
+        .recv_timeout(Duration::from_secs(5))
+        .is_ok());
+    assert_eq!(
+        Config::load(&path).unwrap().renderer.as_deref(),
+        Some("braille")
+    );
+
+    std::fs::remove_dir_all(&dir).unwrap();
+}
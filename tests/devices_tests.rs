@@ -0,0 +1,83 @@
+use ascii_webcam::app::App;
+use ascii_webcam::devices::{list_video4linux, CameraInfo, DevicePicker};
+use ratatui::{backend::TestBackend, Terminal};
+use std::fs;
+use std::path::PathBuf;
+
+fn camera(index: i32, name: &str) -> CameraInfo {
+    CameraInfo {
+        index,
+        name: name.to_string(),
+        path: None,
+    }
+}
+
+#[test]
+fn test_list_video4linux() {
+    let class = std::env::temp_dir().join(format!("ascii-webcam-v4l-{}", std::process::id()));
+    for (node, name, index) in [
+        ("video2", "USB Camera", "0"),
+        ("video1", "Integrated Camera", "1"),
+        ("video0", "Integrated Camera", "0"),
+    ] {
+        fs::create_dir_all(class.join(node)).unwrap();
+        fs::write(class.join(node).join("name"), format!("{name}\n")).unwrap();
+        fs::write(class.join(node).join("index"), index).unwrap();
+    }
+    fs::create_dir_all(class.join("v4l-subdev0")).unwrap();
+
+    let cameras = list_video4linux(&class);
+    fs::remove_dir_all(&class).unwrap();
+
+    // Metadata nodes and other devices are skipped.
+    assert_eq!(cameras.len(), 2);
+    assert_eq!(cameras[0].index, 0);
+    assert_eq!(cameras[0].path, Some(PathBuf::from("/dev/video0")));
+    assert_eq!(cameras[1].to_string(), "2: USB Camera (/dev/video2)");
+}
+
+#[test]
+fn test_device_picker_navigation() {
+    let mut picker = DevicePicker::new(vec![camera(0, "a"), camera(2, "b")], Some(2));
+    assert_eq!(picker.selected().map(|camera| camera.index), Some(2));
+    picker.next();
+    assert_eq!(picker.selected().map(|camera| camera.index), Some(0));
+    picker.previous();
+    assert_eq!(picker.selected, 1);
+
+    let mut empty = DevicePicker::new(Vec::new(), None);
+    empty.next();
+    assert!(empty.selected().is_none());
+}
+
+#[test]
+fn test_device_picker_renders() {
+    let mut app = App::new();
+    app.open_device_picker(vec![camera(0, "Integrated Camera")], Some(0));
+    let mut terminal = Terminal::new(TestBackend::new(80, 24)).unwrap();
+    terminal.draw(|f| app.render(f)).unwrap();
+
+    let text: String = terminal
+        .backend()
+        .buffer()
+        .content()
+        .iter()
+        .map(|cell| cell.symbol())
+        .collect();
+    assert!(text.contains("> 0: Integrated Camera"));
+
+    app.close_device_picker();
+    assert!(app.device_picker.is_none());
+
+    // The picker opens right away, while the cameras are listed in the background
+    app.device_picker = Some(DevicePicker::searching());
+    terminal.draw(|f| app.render(f)).unwrap();
+    let text: String = terminal
+        .backend()
+        .buffer()
+        .content()
+        .iter()
+        .map(|cell| cell.symbol())
+        .collect();
+    assert!(text.contains("Searching for cameras"));
+}
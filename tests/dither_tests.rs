@@ -0,0 +1,50 @@
+use ascii_webcam::dither::{bayer_index, dither, Dither};
+
+/// A horizontal gradient from black to white.
+fn gradient(width: usize, height: usize) -> Vec<u8> {
+    (0..height)
+        .flat_map(|_| (0..width).map(move |x| u8::try_from(x * 255 / (width - 1)).unwrap()))
+        .collect()
+}
+
+#[test]
+fn test_bayer_matrices_are_permutations() {
+    for order in 1..=3 {
+        let size = 1 << order;
+        let mut entries: Vec<usize> = (0..size)
+            .flat_map(|y| (0..size).map(move |x| bayer_index(order, x, y)))
+            .collect();
+        entries.sort_unstable();
+        assert_eq!(entries, (0..size * size).collect::<Vec<_>>());
+    }
+}
+
+#[test]
+fn test_dither_preserves_flat_extremes() {
+    for method in Dither::ALL {
+        assert!(dither(&[0; 64], 8, 4, method).iter().all(|&l| l == 0));
+        assert!(dither(&[255; 64], 8, 4, method).iter().all(|&l| l == 3));
+    }
+}
+
+#[test]
+fn test_dither_preserves_average_brightness() {
+    // A flat mid-gray quantized to black and white should come out about half lit.
+    let pixels = vec![128; 32 * 32];
+    for method in Dither::ALL.into_iter().filter(|&m| m != Dither::None) {
+        let lit = dither(&pixels, 32, 2, method)
+            .iter()
+            .filter(|&&l| l == 1)
+            .count();
+        let ratio = lit as f32 / pixels.len() as f32;
+        assert!(
+            (0.4..=0.6).contains(&ratio),
+            "{method} lit {ratio} of the pixels"
+        );
+    }
+
+    // Without dithering, the whole gradient still uses every level.
+    let levels = dither(&gradient(64, 1), 64, 10, Dither::None);
+    assert_eq!(levels.first(), Some(&0));
+    assert_eq!(levels.last(), Some(&9));
+}
@@ -0,0 +1,48 @@
+use ascii_webcam::edges::{detect_edges, edge_glyph, EdgeMode};
+use opencv::{core, imgproc};
+
+/// A 16x8 grayscale image, black on the left half and white on the right half.
+fn vertical_edge() -> core::Mat {
+    let mut image =
+        core::Mat::new_rows_cols_with_default(8, 16, core::CV_8UC1, core::Scalar::all(0.0))
+            .unwrap();
+    let _ = imgproc::rectangle(
+        &mut image,
+        core::Rect::new(8, 0, 8, 8),
+        core::Scalar::all(255.0),
+        -1,
+        imgproc::LINE_8,
+        0,
+    );
+    image
+}
+
+#[test]
+fn test_edge_glyph_directions() {
+    assert_eq!(edge_glyph(-1.0, 0.0), '|');
+    assert_eq!(edge_glyph(-1.0, -1.0), '/');
+    assert_eq!(edge_glyph(-1.0, 1.0), '\\');
+    assert_eq!(edge_glyph(0.1, 2.0), '_');
+}
+
+#[test]
+fn test_detect_edges() {
+    let image = vertical_edge();
+
+    let off = detect_edges(&image, EdgeMode::Off, 48).unwrap();
+    assert_eq!(off.len(), 16 * 8);
+    assert!(off.iter().all(Option::is_none));
+
+    for mode in [EdgeMode::Sobel, EdgeMode::Canny] {
+        let edges = detect_edges(&image, mode, 48).unwrap();
+        assert_eq!(edges.len(), 16 * 8);
+        assert!(
+            edges[3 * 16].is_none(),
+            "{mode} found an edge in a flat area"
+        );
+        assert!(
+            edges[3 * 16 + 7..3 * 16 + 9].contains(&Some('|')),
+            "{mode} missed the vertical edge"
+        );
+    }
+}
@@ -0,0 +1,95 @@
+use ascii_webcam::app::App;
+use ascii_webcam::exposure::{measure, AutoExposure, Levels};
+use opencv::{core, imgproc};
+
+/// A 100x1 grayscale image with values 0 to 99.
+fn gradient() -> core::Mat {
+    let mut image =
+        core::Mat::new_rows_cols_with_default(1, 100, core::CV_8UC1, core::Scalar::all(0.0))
+            .unwrap();
+    for x in 0..100 {
+        let _ = imgproc::rectangle(
+            &mut image,
+            core::Rect::new(x, 0, 1, 1),
+            core::Scalar::all(f64::from(x)),
+            -1,
+            imgproc::LINE_8,
+            0,
+        );
+    }
+    image
+}
+
+#[test]
+fn test_measure() {
+    let levels = measure(&gradient(), 0.1, 0.1).unwrap();
+    assert_eq!(levels, Levels::new(10, 89));
+
+    let levels = measure(&gradient(), 0.0, 0.0).unwrap();
+    assert_eq!(levels, Levels::new(0, 99));
+}
+
+#[test]
+fn test_auto_exposure_smoothing() {
+    let mut exposure = AutoExposure::default();
+    exposure.smoothing = 0.5;
+    exposure.min_range = 0;
+    let dark = core::Mat::new_rows_cols_with_default(10, 10, core::CV_8UC1, core::Scalar::all(0.0))
+        .unwrap();
+    let mut frame = dark.clone();
+    let _ = imgproc::rectangle(
+        &mut frame,
+        core::Rect::new(0, 0, 10, 5),
+        core::Scalar::all(200.0),
+        -1,
+        imgproc::LINE_8,
+        0,
+    );
+
+    // Disabled auto-exposure leaves the full range
+    assert!(exposure.update(&frame).unwrap().is_full_range());
+
+    exposure.toggle();
+    assert_eq!(exposure.update(&frame).unwrap(), Levels::new(0, 200));
+
+    // A change of scene is followed gradually
+    let mut bright = dark;
+    let _ = imgproc::rectangle(
+        &mut bright,
+        core::Rect::new(0, 0, 10, 5),
+        core::Scalar::all(100.0),
+        -1,
+        imgproc::LINE_8,
+        0,
+    );
+    assert_eq!(exposure.update(&bright).unwrap(), Levels::new(0, 150));
+    assert_eq!(exposure.update(&bright).unwrap(), Levels::new(0, 125));
+}
+
+#[test]
+fn test_auto_exposure_in_app() {
+    // A dim BGR frame, with values from 40 to 80
+    let mut frame =
+        core::Mat::new_rows_cols_with_default(40, 80, core::CV_8UC3, core::Scalar::all(40.0))
+            .unwrap();
+    let _ = imgproc::rectangle(
+        &mut frame,
+        core::Rect::new(40, 0, 40, 40),
+        core::Scalar::all(80.0),
+        -1,
+        imgproc::LINE_8,
+        0,
+    );
+
+    let mut app = App::new();
+    app.update(&frame, 8, 4).unwrap();
+    assert!(app.settings.adjustments.levels.is_full_range());
+
+    app.toggle_auto_exposure();
+    app.update(&frame, 8, 4).unwrap();
+    assert_eq!(app.settings.adjustments.levels, Levels::new(40, 80));
+    // The levels are stretched to the whole ramp
+    let luminance: Vec<u8> = app.ascii_frame.cells().map(|cell| cell.luminance).collect();
+    assert!(luminance.contains(&0));
+    assert!(luminance.contains(&255));
+}
@@ -0,0 +1,82 @@
+use ascii_webcam::color::{ColorMode, Rgb};
+use ascii_webcam::frame::{AsciiFrame, Cell};
+use ratatui::style::Color;
+
+fn sample_frame() -> AsciiFrame {
+    let red = Rgb::new(255, 0, 0);
+    let blue = Rgb::new(0, 0, 255);
+    AsciiFrame::from_cells(
+        3,
+        2,
+        vec![
+            Cell::new('a', 10).with_fg(red),
+            Cell::new('b', 20).with_fg(red),
+            Cell::new('c', 30).with_fg(blue),
+            Cell::new('d', 40),
+            Cell::new('e', 50),
+            Cell::new('f', 60).with_bg(blue),
+        ],
+    )
+}
+
+#[test]
+fn test_frame_display_and_access() {
+    let frame = sample_frame();
+    assert_eq!(frame.to_string(), "abc\ndef");
+    assert_eq!(frame.get(2, 1).map(|cell| cell.glyph), Some('f'));
+    assert!(frame.get(3, 0).is_none());
+    assert_eq!(frame.rows().count(), 2);
+    assert_eq!(
+        frame.indexed_cells().map(|(x, y, _)| (x, y)).last(),
+        Some((2, 1))
+    );
+}
+
+#[test]
+fn test_frame_to_text_merges_spans() {
+    let frame = sample_frame();
+
+    let mono = frame.to_text(ColorMode::Mono);
+    assert_eq!(mono.lines.len(), 2);
+    assert_eq!(mono.lines[0].spans.len(), 1);
+
+    let color = frame.to_text(ColorMode::TrueColor);
+    assert_eq!(color.lines[0].spans.len(), 2);
+    assert_eq!(color.lines[0].spans[0].content, "ab");
+    assert_eq!(
+        color.lines[0].spans[0].style.fg,
+        Some(Color::Rgb(255, 0, 0))
+    );
+    assert_eq!(
+        color.lines[1].spans[1].style.bg,
+        Some(Color::Rgb(0, 0, 255))
+    );
+}
+
+#[test]
+#[should_panic(expected = "cell count does not match frame size")]
+fn test_frame_from_cells_size_mismatch() {
+    let _ = AsciiFrame::from_cells(2, 2, vec![Cell::default()]);
+}
+
+#[test]
+fn test_frame_letterbox() {
+    let padded = sample_frame().letterbox(5, 4);
+    assert_eq!(padded.width(), 5);
+    assert_eq!(padded.height(), 4);
+    assert_eq!(padded.to_string(), "     \n abc \n def \n     ");
+
+    // Larger frames are not cropped
+    let unchanged = sample_frame().letterbox(1, 1);
+    assert_eq!(unchanged, sample_frame());
+}
+
+#[test]
+fn test_frame_to_ansi() {
+    let frame = sample_frame();
+    assert_eq!(frame.to_ansi(ColorMode::Mono), frame.to_string());
+    assert_eq!(
+        frame.to_ansi(ColorMode::TrueColor),
+        "\x1b[38;2;255;0;0mab\x1b[0m\x1b[38;2;0;0;255mc\x1b[0m\nde\x1b[48;2;0;0;255mf\x1b[0m"
+    );
+}
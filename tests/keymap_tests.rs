@@ -0,0 +1,92 @@
+use ascii_webcam::app::App;
+use ascii_webcam::config::Config;
+use ascii_webcam::keymap::{parse_key, Action, Keymap};
+use crossterm::event::KeyCode;
+use ratatui::backend::TestBackend;
+use ratatui::Terminal;
+use std::collections::HashSet;
+
+#[test]
+fn test_default_keymap() {
+    let keymap = Keymap::default();
+    let keys = Action::ALL.map(Action::default_key);
+    assert_eq!(keys.iter().collect::<HashSet<_>>().len(), keys.len());
+    for action in Action::ALL {
+        assert_eq!(keymap.action(action.default_key()), Some(action));
+        assert_eq!(Action::from_name(&action.to_string()), Some(action));
+    }
+    assert_eq!(keymap.label(Action::Pause), "Space");
+    assert_eq!(
+        keymap.group_label(&[Action::Darker, Action::Brighter]),
+        "b / B"
+    );
+}
+
+#[test]
+fn test_overrides() {
+    let config = Config::parse(
+        "[keys]\npause = \"p\"\nprevious-frame = \"backspace\"\nloop = \"\"\nquit = \"F10\"",
+    )
+    .unwrap();
+    let keymap = config.keymap().unwrap();
+    assert_eq!(keymap.action(KeyCode::Char('p')), Some(Action::Pause));
+    assert_eq!(keymap.action(KeyCode::Char(' ')), None);
+    assert_eq!(
+        keymap.action(KeyCode::Backspace),
+        Some(Action::PreviousFrame)
+    );
+    assert_eq!(keymap.action(KeyCode::Char('l')), None);
+    assert_eq!(keymap.label(Action::Loop), "-");
+    assert_eq!(keymap.action(KeyCode::F(10)), Some(Action::Quit));
+
+    assert_eq!(parse_key("PageUp").unwrap(), KeyCode::PageUp);
+    assert!(parse_key("hyper").is_err());
+    assert!(Config::parse("[keys]\njump = \"j\"")
+        .unwrap()
+        .keymap()
+        .is_err());
+    assert!(Config::parse("[keys]\nquit = \"f13\"")
+        .unwrap()
+        .keymap()
+        .is_err());
+
+    assert!(Config::parse("[keys]\npause = \"x\"\nstep = \"x\"")
+        .unwrap()
+        .keymap()
+        .is_err());
+}
+
+#[test]
+fn test_quit_stays_bound() {
+    for keys in ["quit = \"\"", "pause = \"q\""] {
+        let config = Config::parse(&format!("[keys]\n{keys}")).unwrap();
+        assert!(config.keymap().is_err(), "{keys}");
+    }
+    let config = Config::parse("[keys]\npause = \"q\"\nquit = \"x\"").unwrap();
+    let keymap = config.keymap().unwrap();
+    assert_eq!(keymap.action(KeyCode::Char('q')), Some(Action::Pause));
+    assert_eq!(keymap.action(KeyCode::Char('x')), Some(Action::Quit));
+}
+
+#[test]
+fn test_help_shows_bound_keys() {
+    let mut app = App::new();
+    app.keymap = Config::parse("[keys]\ncolor = \"x\"")
+        .unwrap()
+        .keymap()
+        .unwrap();
+    app.toggle_help();
+
+    let mut terminal = Terminal::new(TestBackend::new(120, 80)).unwrap();
+    terminal.draw(|f| app.render(f)).unwrap();
+    let screen = terminal
+        .backend()
+        .buffer()
+        .content()
+        .iter()
+        .map(|cell| cell.symbol())
+        .collect::<String>();
+    assert!(screen.contains("Press x to toggle colors"));
+    assert!(screen.contains("Color <x>"));
+    assert!(!screen.contains("Press c to"));
+}
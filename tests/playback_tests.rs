@@ -0,0 +1,67 @@
+use ascii_webcam::playback::{format_time, Playback, PlaybackClock};
+use ascii_webcam::source::FrameSource;
+use ascii_webcam::synthetic::SyntheticSource;
+use std::time::Duration;
+
+#[test]
+fn test_playback_seek_is_clamped() {
+    let mut playback = Playback::new(Some(Duration::from_secs(30)));
+    playback.position = Duration::from_secs(10);
+
+    playback.seek_by(5.0);
+    playback.seek_by(5.0);
+    assert_eq!(playback.take_seek(), Some(Duration::from_secs(20)));
+    assert_eq!(playback.take_seek(), None);
+
+    playback.seek_by(60.0);
+    assert_eq!(playback.take_seek(), Some(Duration::from_secs(30)));
+    playback.seek_by(-60.0);
+    assert_eq!(playback.take_seek(), Some(Duration::ZERO));
+}
+
+#[test]
+fn test_playback_speed_and_step() {
+    let mut playback = Playback::new(None);
+    playback.slower();
+    assert!((playback.speed - 0.75).abs() < f64::EPSILON);
+    for _ in 0..10 {
+        playback.faster();
+    }
+    assert!((playback.speed - 4.0).abs() < f64::EPSILON);
+
+    playback.step();
+    assert!(playback.paused);
+    assert!(playback.take_step());
+    assert!(!playback.take_step());
+    assert_eq!(playback.progress(), None);
+}
+
+#[test]
+fn test_playback_display() {
+    let mut playback = Playback::new(Some(Duration::from_secs(125)));
+    playback.position = Duration::from_secs(62);
+    playback.toggle_loop();
+    assert_eq!(playback.to_string(), "▶ 1:02 / 2:05 | 1x | loop");
+    assert_eq!(format_time(Duration::from_secs(7)), "0:07");
+}
+
+#[test]
+fn test_playback_clock() {
+    let mut clock = PlaybackClock::default();
+    let start = clock.due(Duration::from_secs(10), 2.0);
+    let due = clock.due(Duration::from_secs(12), 2.0);
+    assert_eq!(due - start, Duration::from_secs(1));
+
+    // Going back in time restarts the clock.
+    let restart = clock.due(Duration::ZERO, 2.0);
+    let due = clock.due(Duration::from_secs(1), 2.0);
+    assert_eq!(due - restart, Duration::from_millis(500));
+}
+
+#[test]
+fn test_synthetic_source_seek() {
+    let mut source = SyntheticSource::new(16, 8, 10.0);
+    assert!(source.seek(Duration::from_millis(1500)).unwrap());
+    let frame = source.next_frame().unwrap().unwrap();
+    assert_eq!(frame.timestamp, Duration::from_millis(1500));
+}
@@ -0,0 +1,49 @@
+use ascii_webcam::ramp::{CharRamp, RampPreset};
+
+#[test]
+fn test_preset_ramps() {
+    let classic = CharRamp::default();
+    assert_eq!(classic.preset_kind(), Some(RampPreset::Classic));
+    assert_eq!(classic.glyph(0), ' ');
+    assert_eq!(classic.glyph(255), '@');
+
+    let bourke = CharRamp::preset(RampPreset::Bourke);
+    assert_eq!(bourke.len(), 70);
+    assert_eq!(bourke.glyph(255), '$');
+
+    let binary = CharRamp::preset(RampPreset::Binary);
+    assert_eq!(binary.glyph(127), '0');
+    assert_eq!(binary.glyph(255), '1');
+}
+
+#[test]
+fn test_custom_ramp() {
+    let ramp = CharRamp::new("·░▒▓█").unwrap();
+    assert_eq!(ramp.len(), 5);
+    assert_eq!(ramp.preset_kind(), None);
+    assert_eq!(ramp.glyph(0), '·');
+    assert_eq!(ramp.glyph(128), '▒');
+    assert_eq!(ramp.to_string(), "custom");
+
+    assert!(CharRamp::new("x").is_err());
+    assert!(CharRamp::new("").is_err());
+}
+
+#[test]
+fn test_ramp_inversion_and_cycling() {
+    let mut ramp = CharRamp::default();
+    ramp.invert();
+    assert!(ramp.is_inverted());
+    assert_eq!(ramp.glyph(0), '@');
+    assert_eq!(ramp.to_string(), "classic (inverted)");
+
+    let next = ramp.next_preset();
+    assert_eq!(next.preset_kind(), Some(RampPreset::Bourke));
+    assert!(next.is_inverted());
+
+    assert_eq!(RampPreset::Binary.next(), RampPreset::Classic);
+    assert_eq!(
+        CharRamp::new("ab").unwrap().next_preset().preset_kind(),
+        Some(RampPreset::Classic)
+    );
+}
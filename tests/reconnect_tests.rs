@@ -0,0 +1,78 @@
+use ascii_webcam::app::App;
+use ascii_webcam::reconnect::{Connection, ReconnectPolicy, Reconnector};
+use ratatui::{backend::TestBackend, Terminal};
+use std::time::Duration;
+
+#[test]
+fn test_lost_after_consecutive_failures() {
+    let mut reconnector = Reconnector::default();
+    for _ in 0..4 {
+        assert!(!reconnector.record_failure());
+    }
+    // A successful read starts the count again.
+    assert!(!reconnector.record_success());
+    for _ in 0..4 {
+        assert!(!reconnector.record_failure());
+    }
+    assert!(reconnector.record_failure());
+    assert!(reconnector.is_lost());
+    assert!(reconnector.record_success());
+    assert!(!reconnector.is_lost());
+}
+
+#[test]
+fn test_backoff_delays() {
+    let policy = ReconnectPolicy {
+        failure_threshold: 1,
+        max_attempts: 6,
+        initial_delay: Duration::from_millis(500),
+        max_delay: Duration::from_secs(4),
+    };
+    let mut reconnector = Reconnector::new(policy);
+    assert!(reconnector.record_failure());
+
+    let mut delays = Vec::new();
+    while let Some(connection) = reconnector.start_attempt() {
+        assert_eq!(
+            connection,
+            Connection::Reconnecting {
+                attempt: reconnector.attempts(),
+                max_attempts: 6,
+            }
+        );
+        delays.push(reconnector.delay().as_millis());
+    }
+    assert_eq!(delays, [500, 1000, 2000, 4000, 4000, 4000]);
+    assert_eq!(reconnector.attempts(), 6);
+}
+
+#[test]
+fn test_reconnecting_overlay() {
+    let mut app = App::new();
+    let mut terminal = Terminal::new(TestBackend::new(80, 24)).unwrap();
+    app.connection = Connection::Reconnecting {
+        attempt: 3,
+        max_attempts: 10,
+    };
+    terminal.draw(|f| app.render(f)).unwrap();
+
+    let text: String = terminal
+        .backend()
+        .buffer()
+        .content()
+        .iter()
+        .map(|cell| cell.symbol())
+        .collect();
+    assert!(text.contains("camera lost — reconnecting (attempt 3/10)"));
+
+    app.connection = Connection::Connected;
+    terminal.draw(|f| app.render(f)).unwrap();
+    let text: String = terminal
+        .backend()
+        .buffer()
+        .content()
+        .iter()
+        .map(|cell| cell.symbol())
+        .collect();
+    assert!(!text.contains("camera lost"));
+}
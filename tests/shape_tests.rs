@@ -0,0 +1,40 @@
+use ascii_webcam::shape::{coverage, encode, glyphs, match_block};
+
+#[test]
+fn test_coverage() {
+    assert_eq!(coverage(' '), Some([0; 8]));
+    assert_eq!(coverage('|'), Some([2, 2, 2, 2, 2, 2, 2, 2]));
+    assert_eq!(coverage('-'), Some([0, 0, 3, 3, 0, 0, 0, 0]));
+    assert!(glyphs().all(|glyph| glyph.is_ascii()));
+}
+
+#[test]
+fn test_match_block() {
+    assert_eq!(match_block(&[0; 8]), ' ');
+    assert_eq!(match_block(&[0, 0, 0, 0, 0, 0, 255, 255]), '_');
+    assert_eq!(match_block(&[0, 0, 100, 100, 0, 0, 0, 0]), '-');
+
+    // Brighter blocks are matched with denser glyphs
+    let density = |glyph| {
+        coverage(glyph)
+            .unwrap()
+            .iter()
+            .map(|&c| u32::from(c))
+            .sum::<u32>()
+    };
+    assert!(density(match_block(&[255; 8])) > density(match_block(&[100; 8])));
+}
+
+#[test]
+fn test_encode() {
+    // Two cells: a bright left half and a bright right half
+    let mut pixels = vec![0u8; 4 * 4];
+    for y in 0..4 {
+        pixels[y * 4] = 255;
+        pixels[y * 4 + 3] = 255;
+    }
+    let glyphs = encode(&pixels, 2, 1);
+    assert_eq!(glyphs.len(), 2);
+    assert_ne!(glyphs[0], ' ');
+    assert_ne!(glyphs[0], glyphs[1]);
+}
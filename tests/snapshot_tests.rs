@@ -0,0 +1,72 @@
+use ascii_webcam::color::{ColorMode, Rgb};
+use ascii_webcam::frame::{AsciiFrame, Cell};
+use ascii_webcam::snapshot::SnapshotFormat;
+use std::path::Path;
+
+fn sample_frame() -> AsciiFrame {
+    let red = Rgb::new(255, 0, 0);
+    AsciiFrame::from_cells(
+        3,
+        2,
+        vec![
+            Cell::new('&', 10).with_fg(red),
+            Cell::new('#', 20).with_fg(red),
+            Cell::new(' ', 30),
+            Cell::new('▀', 40)
+                .with_fg(Rgb::new(0, 255, 0))
+                .with_bg(Rgb::new(0, 0, 255)),
+            Cell::new('.', 50),
+            Cell::new('<', 60),
+        ],
+    )
+}
+
+#[test]
+fn test_text_and_ansi() {
+    let frame = sample_frame();
+    assert_eq!(
+        SnapshotFormat::Text.render(&frame, ColorMode::TrueColor),
+        "&# \n▀.<\n"
+    );
+    assert_eq!(
+        SnapshotFormat::Ansi.render(&frame, ColorMode::Mono),
+        "&# \n▀.<\n"
+    );
+    assert!(SnapshotFormat::Ansi
+        .render(&frame, ColorMode::Ansi256)
+        .starts_with("\x1b[38;5;196m&#\x1b[0m"));
+
+    for format in SnapshotFormat::ALL {
+        let path = format!("snapshot.{format}");
+        let expected = (format != SnapshotFormat::Text).then_some(format);
+        assert_eq!(SnapshotFormat::from_path(Path::new(&path)), expected);
+    }
+}
+
+#[test]
+fn test_html() {
+    let frame = sample_frame();
+    let html = SnapshotFormat::Html.render(&frame, ColorMode::TrueColor);
+    assert!(html.starts_with("<!DOCTYPE html>"));
+    assert!(html.contains("<span style=\"color:#ff0000;\">&amp;#</span> \n"));
+    assert!(html.contains("<span style=\"color:#00ff00;background:#0000ff;\">▀</span>.&lt;"));
+
+    let mono = SnapshotFormat::Html.render(&frame, ColorMode::Mono);
+    assert!(!mono.contains("<span"));
+    assert!(mono.contains("&amp;# \n▀.&lt;</pre>"));
+}
+
+#[test]
+fn test_svg() {
+    let frame = sample_frame();
+    let svg = SnapshotFormat::Svg.render(&frame, ColorMode::TrueColor);
+    assert!(svg.starts_with("<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"24\" height=\"32\""));
+    // The background of the half block is drawn behind its cell.
+    assert!(svg.contains("<rect x=\"0\" y=\"16\" width=\"8\" height=\"16\" fill=\"#0000ff\"/>"));
+    assert!(svg.contains("<tspan fill=\"#ff0000\">&amp;#</tspan> </text>"));
+    assert!(svg.trim_end().ends_with("</svg>"));
+
+    let mono = SnapshotFormat::Svg.render(&frame, ColorMode::Mono);
+    assert!(!mono.contains("<tspan"));
+    assert_eq!(mono.matches("<rect").count(), 1);
+}
@@ -0,0 +1,80 @@
+use ascii_webcam::image::ImageSource;
+use ascii_webcam::source::{open_path, FrameSource};
+use ascii_webcam::synthetic::SyntheticSource;
+use opencv::prelude::*;
+use std::path::PathBuf;
+use std::time::Duration;
+
+#[test]
+fn test_synthetic_source() {
+    let mut source = SyntheticSource::new(64, 48, 25.0);
+    let properties = source.properties();
+    assert_eq!((properties.width, properties.height), (64, 48));
+    assert_eq!(properties.fps, Some(25.0));
+    assert_eq!(properties.frame_count, None);
+
+    let first = source.next_frame().unwrap().unwrap();
+    let second = source.next_frame().unwrap().unwrap();
+    assert_eq!(first.image.cols(), 64);
+    assert_eq!(first.image.rows(), 48);
+    assert_eq!(second.timestamp, Duration::from_millis(40));
+    assert_ne!(
+        first.image.data_bytes().unwrap(),
+        second.image.data_bytes().unwrap()
+    );
+}
+
+#[test]
+fn test_image_sequence_ends() {
+    let cat = PathBuf::from("assets/pexels-cat.jpg");
+    let mut source = ImageSource::from_paths(vec![cat.clone(), cat])
+        .unwrap()
+        .with_interval(Duration::from_millis(500));
+    assert_eq!(source.properties().frame_count, Some(2));
+
+    let first = source.next_frame().unwrap().unwrap();
+    assert!(!first.image.empty());
+    assert_eq!(first.timestamp, Duration::ZERO);
+    let second = source.next_frame().unwrap().unwrap();
+    assert_eq!(second.timestamp, Duration::from_millis(500));
+    assert!(source.next_frame().unwrap().is_none());
+    assert!(source.properties().width > 0);
+}
+
+#[test]
+fn test_image_source_errors() {
+    assert!(ImageSource::from_paths(Vec::new()).is_err());
+
+    let mut missing = ImageSource::open("assets/missing.png".as_ref()).unwrap();
+    assert!(missing.next_frame().is_err());
+}
+
+#[test]
+fn test_image_source_pattern() {
+    let source = ImageSource::open("assets/*.jpg".as_ref()).unwrap();
+    assert_eq!(source.len(), 1);
+    assert!(ImageSource::open("assets/*.gif".as_ref()).is_err());
+
+    let source = open_path("assets".as_ref()).unwrap();
+    assert_eq!(source.properties().frame_count, Some(1));
+}
+
+#[test]
+fn test_image_slideshow_seek() {
+    let cat = PathBuf::from("assets/pexels-cat.jpg");
+    let mut source = ImageSource::from_paths(vec![cat.clone(), cat.clone(), cat]).unwrap();
+    let interval = ImageSource::DEFAULT_INTERVAL.as_secs_f64();
+
+    // Relative seeks in seconds land on the neighbouring image despite rounding.
+    assert!(source
+        .seek(Duration::from_secs_f64(interval * 2.0 - 1e-9))
+        .unwrap());
+    assert_eq!(
+        source.next_frame().unwrap().unwrap().timestamp,
+        ImageSource::DEFAULT_INTERVAL * 2
+    );
+    assert!(source.next_frame().unwrap().is_none());
+
+    source.seek(Duration::from_secs(3600)).unwrap();
+    assert!(source.next_frame().unwrap().is_some());
+}
@@ -0,0 +1,89 @@
+use ascii_webcam::source::FrameSource;
+use ascii_webcam::stream::{RawFormat, StreamSource};
+use opencv::prelude::*;
+use std::io::Cursor;
+use std::time::Duration;
+
+#[test]
+fn test_y4m_420_frames() {
+    // Two 2x2 frames: gray, then pure red in BT.601 limited range.
+    let mut data = b"YUV4MPEG2 W2 H2 F30000:1001 Ip A1:1 C420jpeg XYSCSS=420JPEG\n".to_vec();
+    data.extend(b"FRAME\n");
+    data.extend([126, 126, 126, 126, 128, 128]);
+    data.extend(b"FRAME Ixyz\n");
+    data.extend([81, 81, 81, 81, 90, 240]);
+
+    let mut source = StreamSource::y4m(Cursor::new(data), "pipe").unwrap();
+    let properties = source.properties();
+    assert_eq!((properties.width, properties.height), (2, 2));
+    assert!((properties.fps.unwrap() - 29.97).abs() < 0.01);
+
+    let gray = source.next_frame().unwrap().unwrap();
+    assert_eq!(&gray.image.data_bytes().unwrap()[..3], &[128, 128, 128]);
+    let red = source.next_frame().unwrap().unwrap();
+    assert_eq!(&red.image.data_bytes().unwrap()[..3], &[0, 0, 255]);
+    assert_eq!(red.timestamp, Duration::from_secs_f64(1001.0 / 30000.0));
+    assert!(source.next_frame().unwrap().is_none());
+}
+
+#[test]
+fn test_y4m_errors() {
+    assert!(StreamSource::y4m(Cursor::new(Vec::new()), "pipe").is_err());
+    assert!(StreamSource::y4m(Cursor::new(b"P6 2 2 255\n".to_vec()), "pipe").is_err());
+    assert!(StreamSource::y4m(
+        Cursor::new(b"YUV4MPEG2 W2 H2 F25:1 C420p10\n".to_vec()),
+        "pipe"
+    )
+    .is_err());
+    assert!(StreamSource::y4m(Cursor::new(b"YUV4MPEG2 W2 F25:1\n".to_vec()), "pipe").is_err());
+
+    let data = b"YUV4MPEG2 W2 H2 F25:1 Cmono\nFRAME\n\x10\x10\x10\x10JUNK\n".to_vec();
+    let mut source = StreamSource::y4m(Cursor::new(data), "pipe").unwrap();
+    assert!(source.next_frame().unwrap().is_some());
+    assert!(source.next_frame().is_err());
+}
+
+#[test]
+fn test_raw_bgr_frames() {
+    let format: RawFormat = "2x1@10".parse().unwrap();
+    // One frame and a truncated one, which ends the stream.
+    let data = vec![1, 2, 3, 4, 5, 6, 7, 8];
+    let mut source = StreamSource::raw(Cursor::new(data), "pipe", format);
+
+    let frame = source.next_frame().unwrap().unwrap();
+    assert_eq!(frame.image.cols(), 2);
+    assert_eq!(frame.image.data_bytes().unwrap(), &[1, 2, 3, 4, 5, 6]);
+    assert!(source.next_frame().unwrap().is_none());
+
+    assert!("0x480".parse::<RawFormat>().is_err());
+    assert!("640x480@0".parse::<RawFormat>().is_err());
+    assert_eq!(
+        "640x480".parse::<RawFormat>().unwrap().to_string(),
+        "640x480@30"
+    );
+}
+
+#[test]
+fn test_oversized_frames() {
+    // Sizes from untrusted headers are rejected before anything is allocated.
+    let header = b"YUV4MPEG2 W99999 H99999 F25:1\n".to_vec();
+    let Err(err) = StreamSource::y4m(Cursor::new(header), "pipe") else {
+        panic!("oversized frames were accepted");
+    };
+    assert!(err.to_string().contains("exceeds the limit"), "{err}");
+
+    assert!("99999x99999".parse::<RawFormat>().is_err());
+    assert!("8192x8192".parse::<RawFormat>().is_ok());
+    // Each side is limited, not only the number of pixels.
+    assert!("100000x16".parse::<RawFormat>().is_err());
+    let header = b"YUV4MPEG2 W16 H100000 F25:1\n".to_vec();
+    assert!(StreamSource::y4m(Cursor::new(header), "pipe").is_err());
+
+    let format = RawFormat {
+        width: 100_000,
+        height: 100_000,
+        fps: 30.0,
+    };
+    let mut source = StreamSource::raw(Cursor::new(vec![0; 3]), "pipe", format);
+    assert!(source.next_frame().is_err());
+}
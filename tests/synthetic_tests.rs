@@ -0,0 +1,71 @@
+use ascii_webcam::app::App;
+use ascii_webcam::source::FrameSource;
+use ascii_webcam::synthetic::{Pattern, SyntheticSource};
+use opencv::prelude::*;
+use ratatui::{backend::TestBackend, layout::Rect, Terminal};
+
+fn pixels(source: &mut SyntheticSource) -> Vec<u8> {
+    let frame = source.next_frame().unwrap().unwrap();
+    frame.image.data_bytes().unwrap().to_vec()
+}
+
+#[test]
+fn test_color_bars() {
+    let mut source = SyntheticSource::new(70, 2, 30.0).with_pattern(Pattern::ColorBars);
+    let first = pixels(&mut source);
+    // White on the left, blue on the right, in BGR order.
+    assert_eq!(&first[..3], &[191, 191, 191]);
+    assert_eq!(&first[69 * 3..70 * 3], &[191, 0, 0]);
+    assert_eq!(first, pixels(&mut source));
+}
+
+#[test]
+fn test_patterns_are_deterministic() {
+    for pattern in Pattern::ALL {
+        let mut first = SyntheticSource::new(32, 24, 30.0).with_pattern(pattern);
+        let mut second = SyntheticSource::new(32, 24, 30.0).with_pattern(pattern);
+        let frames = (pixels(&mut first), pixels(&mut first));
+        assert_eq!(frames.0, pixels(&mut second), "{pattern}");
+        if pattern != Pattern::ColorBars {
+            assert_ne!(frames.0, frames.1, "{pattern} does not move");
+        }
+        assert_eq!(Pattern::from_name(&pattern.to_string()), Some(pattern));
+    }
+    assert_eq!(Pattern::Noise.next(), Pattern::ColorBars);
+}
+
+#[test]
+fn test_bouncing_box_stays_inside() {
+    let mut source = SyntheticSource::new(40, 20, 30.0).with_pattern(Pattern::BouncingBox);
+    for _ in 0..50 {
+        // The box is 5x5 pixels and always fully visible.
+        let white = pixels(&mut source)
+            .chunks_exact(3)
+            .filter(|pixel| pixel[0] == 255)
+            .count();
+        assert_eq!(white, 25);
+    }
+}
+
+#[test]
+fn test_synthetic_pipeline_renders_headless() {
+    let mut source = SyntheticSource::new(320, 240, 30.0)
+        .with_pattern(Pattern::BouncingBox)
+        .with_counter(true);
+    let mut app = App::new();
+    let mut terminal = Terminal::new(TestBackend::new(80, 24)).unwrap();
+    let area = app.frame_area(Rect::new(0, 0, 80, 24));
+
+    for _ in 0..3 {
+        let frame = source.next_frame().unwrap().unwrap();
+        app.update(&frame.image, i32::from(area.width), i32::from(area.height))
+            .unwrap();
+        terminal.draw(|f| app.render(f)).unwrap();
+    }
+
+    assert_eq!(app.ascii_frame.width(), usize::from(area.width));
+    let buffer = terminal.backend().buffer();
+    let text: String = buffer.content().iter().map(|cell| cell.symbol()).collect();
+    assert!(text.contains("Stats"));
+    assert!(text.contains("Quit"));
+}
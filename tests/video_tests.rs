@@ -1,4 +1,5 @@
-use ascii_webcam::video::VideoCapture;
+use ascii_webcam::source::SourceProperties;
+use ascii_webcam::video::{fourcc_code, fourcc_name, CaptureFormat, VideoCapture};
 use opencv::core::MatTraitConst;
 
 #[test]
@@ -17,3 +18,39 @@ fn test_read_frame() {
     let frame = result.unwrap();
     assert!(!frame.empty());
 }
+
+#[test]
+fn test_capture_format_display() {
+    let format = CaptureFormat::default();
+    assert!(format.is_default());
+    assert_eq!(format.to_string(), "default");
+
+    let format = CaptureFormat {
+        width: Some(1280),
+        height: Some(720),
+        fps: Some(60.0),
+        fourcc: Some("MJPG".to_string()),
+    };
+    assert!(!format.is_default());
+    assert_eq!(format.to_string(), "1280x720 @ 60 fps MJPG");
+}
+
+#[test]
+fn test_fourcc_codes() {
+    let code = fourcc_code("YUYV").unwrap();
+    assert_eq!(code, i32::from_le_bytes(*b"YUYV"));
+    assert_eq!(fourcc_name(code).as_deref(), Some("YUYV"));
+    assert_eq!(fourcc_name(0), None);
+    assert!(fourcc_code("MJP").is_err());
+    assert!(fourcc_code("MJPÉ").is_err());
+
+    let properties = SourceProperties {
+        name: "camera 0".to_string(),
+        width: 640,
+        height: 480,
+        fps: Some(30.0),
+        frame_count: None,
+        format: fourcc_name(code),
+    };
+    assert_eq!(properties.to_string(), "camera 0 640x480 @ 30 fps YUYV");
+}